@@ -0,0 +1,432 @@
+// src-tauri/src/backup_archive.rs
+// Archivi di backup cifrati con passphrase: server, snippet, job pianificati e known_hosts
+// Formato: JSON con manifest in chiaro (versioni, contenuto, checksum) e payload cifrato
+// XChaCha20-Poly1305 con chiave derivata da Argon2id; il manifest è autenticato come AAD
+
+use std::fs;
+use std::path::PathBuf;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{command, AppHandle};
+
+use crate::scheduler::{load_scheduled_jobs, replace_scheduled_jobs, CronSchedule, ScheduledJob};
+use crate::server_import::pick_import_file;
+use crate::snapshots::snapshot_before_change;
+use crate::snippets::{load_snippets, store_snippets, Snippet};
+use crate::ssh_config::home_dir;
+use crate::validation::{validate_server, RecordValidation};
+use crate::{load_servers, store_servers, Server};
+
+const ARCHIVE_FORMAT: &str = "devpulse-backup";
+const SCHEMA_VERSION: u32 = 1;
+const ARCHIVE_EXTENSION: &str = "dpbackup";
+const MIN_PASSPHRASE_LEN: usize = 8;
+// Limiti ai costi Argon2 letti dall'archivio (non fidato): oltre si rifiuta prima di derivare la chiave
+const MAX_KDF_MEMORY_KIB: u32 = 1024 * 1024;
+const MAX_KDF_ITERATIONS: u32 = 10;
+const MAX_KDF_PARALLELISM: u32 = 8;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupContents {
+    pub servers: usize,
+    pub snippets: usize,
+    pub schedules: usize,
+    pub known_hosts: usize,              // righe di known_hosts
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub format: String,
+    pub app_version: String,
+    pub schema_version: u32,
+    pub created_at: String,
+    pub contents: BackupContents,
+    pub checksum: String,                // "sha256:<hex>" del payload in chiaro
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct BackupEncryption {
+    cipher: String,                      // "xchacha20poly1305"
+    kdf: String,                         // "argon2id"
+    salt: String,                        // base64
+    nonce: String,                       // base64
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct BackupArchive {
+    manifest: BackupManifest,
+    encryption: BackupEncryption,
+    payload: String,                     // base64 del payload cifrato
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct BackupPayload {
+    servers: Vec<Server>,
+    snippets: Vec<Snippet>,
+    schedules: Vec<ScheduledJob>,
+    known_hosts: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupExportResult {
+    pub path: String,
+    pub manifest: BackupManifest,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupRestoreResult {
+    pub manifest: BackupManifest,
+    pub servers: usize,
+    pub snippets: usize,
+    pub schedules: usize,
+    pub known_hosts_added: usize,
+    pub warnings: Vec<String>,
+    pub issues: Vec<RecordValidation>,   // server con errori o warning (come nell'anteprima di import)
+    pub rejected: usize,                 // server scartati perché non validi
+}
+
+fn known_hosts_path() -> Option<PathBuf> {
+    home_dir().map(|h| h.join(".ssh").join("known_hosts"))
+}
+
+fn known_host_lines(content: &str) -> impl Iterator<Item = &str> {
+    content.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#'))
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], encryption: &BackupEncryption) -> Result<[u8; 32], String> {
+    let params = Params::new(encryption.memory_kib, encryption.iterations, encryption.parallelism, Some(32))
+        .map_err(|e| format!("Parametri di cifratura non validi: {}", e))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Derivazione della chiave fallita: {}", e))?;
+    Ok(key)
+}
+
+// Il manifest serializzato è l'AAD: modificarlo invalida l'archivio
+fn manifest_aad(manifest: &BackupManifest) -> Result<Vec<u8>, String> {
+    serde_json::to_vec(manifest).map_err(|e| e.to_string())
+}
+
+fn encrypt_payload(app_version: String, passphrase: &str, payload: &BackupPayload) -> Result<BackupArchive, String> {
+    let plain = serde_json::to_vec(payload).map_err(|e| e.to_string())?;
+
+    let manifest = BackupManifest {
+        format: ARCHIVE_FORMAT.to_string(),
+        app_version,
+        schema_version: SCHEMA_VERSION,
+        created_at: chrono::Local::now().to_rfc3339(),
+        contents: BackupContents {
+            servers: payload.servers.len(),
+            snippets: payload.snippets.len(),
+            schedules: payload.schedules.len(),
+            known_hosts: payload.known_hosts.as_deref().map_or(0, |k| known_host_lines(k).count()),
+        },
+        checksum: format!("sha256:{}", sha256_hex(&plain)),
+    };
+
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 24];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let defaults = Params::default();
+    let encryption = BackupEncryption {
+        cipher: "xchacha20poly1305".to_string(),
+        kdf: "argon2id".to_string(),
+        salt: STANDARD.encode(salt),
+        nonce: STANDARD.encode(nonce),
+        memory_kib: defaults.m_cost(),
+        iterations: defaults.t_cost(),
+        parallelism: defaults.p_cost(),
+    };
+
+    let key = derive_key(passphrase, &salt, &encryption)?;
+    let aad = manifest_aad(&manifest)?;
+    let cipher = XChaCha20Poly1305::new(&key.into());
+    let encrypted = cipher
+        .encrypt(XNonce::from_slice(&nonce), Payload { msg: &plain, aad: &aad })
+        .map_err(|_| "Cifratura del backup fallita".to_string())?;
+
+    Ok(BackupArchive { manifest, encryption, payload: STANDARD.encode(encrypted) })
+}
+
+// ✅ Decifra e verifica un archivio: formato, versione schema, autenticazione, checksum e contenuto
+fn open_archive(content: &str, passphrase: &str) -> Result<(BackupManifest, BackupPayload), String> {
+    let archive: BackupArchive =
+        serde_json::from_str(content).map_err(|e| format!("Archivio di backup non valido: {}", e))?;
+    let manifest = &archive.manifest;
+    let encryption = &archive.encryption;
+
+    if manifest.format != ARCHIVE_FORMAT {
+        return Err(format!("Formato archivio non riconosciuto: {}", manifest.format));
+    }
+    if manifest.schema_version > SCHEMA_VERSION {
+        return Err(format!(
+            "Backup creato con una versione più recente di DevPulse ({}, schema {}): aggiorna l'app",
+            manifest.app_version, manifest.schema_version
+        ));
+    }
+    if encryption.cipher != "xchacha20poly1305" || encryption.kdf != "argon2id" {
+        return Err(format!("Cifratura non supportata: {} / {}", encryption.cipher, encryption.kdf));
+    }
+    if encryption.memory_kib > MAX_KDF_MEMORY_KIB
+        || encryption.iterations > MAX_KDF_ITERATIONS
+        || encryption.parallelism > MAX_KDF_PARALLELISM
+    {
+        return Err(format!(
+            "Parametri Argon2 fuori dai limiti (memoria {} KiB, iterazioni {}, parallelismo {}): archivio non attendibile",
+            encryption.memory_kib, encryption.iterations, encryption.parallelism
+        ));
+    }
+
+    let decode = |value: &str, what: &str| STANDARD.decode(value).map_err(|_| format!("{} dell'archivio non valido", what));
+    let salt = decode(&encryption.salt, "Salt")?;
+    let nonce = decode(&encryption.nonce, "Nonce")?;
+    let encrypted = decode(&archive.payload, "Payload")?;
+    if nonce.len() != 24 {
+        return Err("Nonce dell'archivio non valido".to_string());
+    }
+
+    let key = derive_key(passphrase, &salt, encryption)?;
+    let aad = manifest_aad(manifest)?;
+    let plain = XChaCha20Poly1305::new(&key.into())
+        .decrypt(XNonce::from_slice(&nonce), Payload { msg: &encrypted, aad: &aad })
+        .map_err(|_| "Passphrase errata o archivio danneggiato".to_string())?;
+
+    if manifest.checksum != format!("sha256:{}", sha256_hex(&plain)) {
+        return Err("Checksum del backup non corrispondente".to_string());
+    }
+
+    let payload: BackupPayload =
+        serde_json::from_slice(&plain).map_err(|e| format!("Contenuto del backup non valido: {}", e))?;
+    let expected = &manifest.contents;
+    if payload.servers.len() != expected.servers
+        || payload.snippets.len() != expected.snippets
+        || payload.schedules.len() != expected.schedules
+    {
+        return Err("Il contenuto del backup non corrisponde al manifest".to_string());
+    }
+
+    Ok((archive.manifest, payload))
+}
+
+// Scarta i record non validi prima di toccare i file locali: il resto del backup viene ripristinato
+// (servers.json non era validato prima, un backup dei dati esistenti deve restare ripristinabile)
+fn filter_payload(payload: &mut BackupPayload, warnings: &mut Vec<String>) -> Vec<RecordValidation> {
+    let mut issues = Vec::new();
+    let mut valid = Vec::new();
+    for (index, server) in payload.servers.drain(..).enumerate() {
+        let mut validation = validate_server(&server);
+        validation.index = index;
+        let accepted = validation.is_valid();
+        if !validation.errors.is_empty() || !validation.warnings.is_empty() {
+            issues.push(validation);
+        }
+        if accepted {
+            valid.push(server);
+        }
+    }
+
+    // Jump host scartato: il server resta, ma senza riferimento pendente
+    let ids: Vec<String> = valid.iter().map(|s| s.id.clone()).collect();
+    for server in &mut valid {
+        if let Some(jump) = server.jump_host.clone().filter(|j| !j.is_empty() && !ids.contains(j)) {
+            warnings.push(format!("{}: jump host '{}' non ripristinato, rimosso", server.name, jump));
+            server.jump_host = None;
+        }
+    }
+    payload.servers = valid;
+
+    payload.schedules.retain(|job| match CronSchedule::parse(&job.cron) {
+        Ok(_) => true,
+        Err(e) => {
+            warnings.push(format!("Job '{}' non ripristinato: {}", job.name, e));
+            false
+        }
+    });
+
+    issues
+}
+
+// Aggiunge le righe mancanti a ~/.ssh/known_hosts (quelle esistenti non vengono toccate)
+fn merge_known_hosts(content: &str) -> Result<usize, String> {
+    let path = known_hosts_path().ok_or("Cartella home non trovata")?;
+    let current = fs::read_to_string(&path).unwrap_or_default();
+    let present: Vec<&str> = known_host_lines(&current).collect();
+
+    let mut missing: Vec<&str> = Vec::new();
+    for line in known_host_lines(content) {
+        if !present.contains(&line) && !missing.contains(&line) {
+            missing.push(line);
+        }
+    }
+    if missing.is_empty() {
+        return Ok(0);
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let mut merged = current;
+    if !merged.is_empty() && !merged.ends_with('\n') {
+        merged.push('\n');
+    }
+    for line in &missing {
+        merged.push_str(line);
+        merged.push('\n');
+    }
+    fs::write(&path, merged).map_err(|e| format!("Impossibile aggiornare {}: {}", path.display(), e))?;
+    Ok(missing.len())
+}
+
+fn check_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!("La passphrase deve avere almeno {} caratteri", MIN_PASSPHRASE_LEN));
+    }
+    Ok(())
+}
+
+// ✅ COMANDO: Crea un archivio di backup cifrato (destinazione scelta con il dialog se non indicata)
+#[command]
+pub async fn export_backup_archive(
+    app: AppHandle,
+    passphrase: String,
+    path: Option<String>,
+    include_known_hosts: Option<bool>,
+) -> Result<BackupExportResult, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    check_passphrase(&passphrase)?;
+
+    let known_hosts = if include_known_hosts.unwrap_or(true) {
+        known_hosts_path().and_then(|p| fs::read_to_string(p).ok())
+    } else {
+        None
+    };
+    let payload = BackupPayload {
+        servers: load_servers(app.clone()).await?,
+        snippets: load_snippets(&app)?,
+        schedules: load_scheduled_jobs(&app)?,
+        known_hosts,
+    };
+
+    let destination = match path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let file_name = format!("devpulse-backup-{}.{}", chrono::Local::now().format("%Y%m%d-%H%M%S"), ARCHIVE_EXTENSION);
+            let file_path = app
+                .dialog()
+                .file()
+                .set_file_name(&file_name)
+                .add_filter("DevPulse backup", &[ARCHIVE_EXTENSION])
+                .blocking_save_file()
+                .ok_or("Nessuna destinazione selezionata")?;
+            file_path.as_path().ok_or("Percorso file non valido")?.to_path_buf()
+        }
+    };
+
+    let app_version = app.package_info().version.to_string();
+    let archive = tokio::task::spawn_blocking(move || encrypt_payload(app_version, &passphrase, &payload))
+        .await
+        .map_err(|e| format!("Errore task backup: {}", e))??;
+
+    let json = serde_json::to_string_pretty(&archive).map_err(|e| e.to_string())?;
+    fs::write(&destination, json).map_err(|e| format!("Impossibile scrivere {}: {}", destination.display(), e))?;
+
+    println!("🔒 Backup cifrato salvato in {}", destination.display());
+    Ok(BackupExportResult {
+        path: destination.to_string_lossy().to_string(),
+        manifest: archive.manifest,
+    })
+}
+
+// ✅ COMANDO: Legge il manifest di un archivio senza decifrarlo
+#[command]
+pub async fn inspect_backup_archive(app: AppHandle, path: Option<String>) -> Result<BackupManifest, String> {
+    let path = pick_import_file(&app, path, "DevPulse backup", &[ARCHIVE_EXTENSION])?;
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let archive: BackupArchive =
+        serde_json::from_str(&content).map_err(|e| format!("Archivio di backup non valido: {}", e))?;
+    Ok(archive.manifest)
+}
+
+// ✅ COMANDO: Ripristina un archivio dopo averne verificato l'integrità
+// I record non validi vengono scartati e riportati in `issues`, il resto viene ripristinato
+#[command]
+pub async fn restore_backup_archive(
+    app: AppHandle,
+    passphrase: String,
+    path: Option<String>,
+    include_known_hosts: Option<bool>,
+) -> Result<BackupRestoreResult, String> {
+    let path = pick_import_file(&app, path, "DevPulse backup", &[ARCHIVE_EXTENSION])?;
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+
+    let (manifest, mut payload) = tokio::task::spawn_blocking(move || open_archive(&content, &passphrase))
+        .await
+        .map_err(|e| format!("Errore task ripristino: {}", e))??;
+
+    let mut warnings = Vec::new();
+    let issues = filter_payload(&mut payload, &mut warnings);
+    let rejected = issues.iter().filter(|i| !i.is_valid()).count();
+    if rejected > 0 {
+        warnings.push(format!("{} server non validi non sono stati ripristinati", rejected));
+    }
+
+    // Solo ora si toccano i file locali (senza nessun server valido quelli locali restano)
+    snapshot_before_change(&app, "ripristino archivio di backup")?;
+    if payload.servers.is_empty() && rejected > 0 {
+        warnings.push("Nessun server valido nel backup: i server locali non sono stati modificati".to_string());
+    } else {
+        store_servers(&app, &payload.servers)?;
+    }
+    store_snippets(&app, &payload.snippets)?;
+    replace_scheduled_jobs(&app, &payload.schedules)?;
+
+    let known_hosts_added = match (&payload.known_hosts, include_known_hosts.unwrap_or(true)) {
+        (Some(known_hosts), true) => merge_known_hosts(known_hosts).unwrap_or_else(|e| {
+            warnings.push(e);
+            0
+        }),
+        _ => 0,
+    };
+
+    println!(
+        "♻️ Backup ripristinato da {}: {} server, {} snippet, {} job",
+        path.display(),
+        payload.servers.len(),
+        payload.snippets.len(),
+        payload.schedules.len()
+    );
+    Ok(BackupRestoreResult {
+        manifest,
+        servers: payload.servers.len(),
+        snippets: payload.snippets.len(),
+        schedules: payload.schedules.len(),
+        known_hosts_added,
+        warnings,
+        issues,
+        rejected,
+    })
+}
@@ -0,0 +1,199 @@
+// src-tauri/src/broadcast.rs
+// Esecuzione dello stesso comando su più server in parallelo
+// Output per host in streaming ("remote_command_output") e riepilogo finale
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Emitter};
+use tokio::sync::Semaphore;
+
+use crate::groups::{load_groups, matches_selector, parse_selector, ServerGroup};
+use crate::remote_exec::{execute_on_server, ExecOptions};
+use crate::{load_servers, new_id, Server};
+
+const DEFAULT_PARALLELISM: usize = 8;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastRequest {
+    pub server_ids: Option<Vec<String>>,
+    pub tag: Option<String>,              // confrontato con il tipo server (come "type:"); per i tag usare selector
+    pub selector: Option<String>,         // es. "group:prod & !tag:db" (vedi groups.rs)
+    pub command: String,
+    pub parallelism: Option<usize>,
+    pub fail_fast: Option<bool>,          // default: continua sugli errori
+    pub options: Option<ExecOptions>,
+    pub broadcast_id: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastHostResult {
+    pub server_id: String,
+    pub server_name: String,
+    pub ip: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    pub timed_out: bool,
+    pub skipped: bool,
+    pub error: Option<String>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastSummary {
+    pub broadcast_id: String,
+    pub command: String,
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub aborted: bool,
+    pub duration_ms: u64,
+    pub results: Vec<BroadcastHostResult>,
+}
+
+// Seleziona i server per id, tipo (`tag`) e/o selettore, mantenendo l'ordine della lista salvata
+pub fn select_servers(
+    servers: &[Server],
+    groups: &[ServerGroup],
+    server_ids: Option<&[String]>,
+    tag: Option<&str>,
+    selector: Option<&str>,
+) -> Result<Vec<Server>, String> {
+    let tag = tag.map(|t| t.trim()).filter(|t| !t.is_empty());
+    let selector = match selector.map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        Some(expression) => Some(parse_selector(expression)?),
+        None => None,
+    };
+
+    Ok(servers
+        .iter()
+        .filter(|s| {
+            let by_id = server_ids.map_or(false, |ids| ids.contains(&s.id));
+            let by_tag = tag.map_or(false, |t| s.server_type.to_lowercase() == t.to_lowercase());
+            let by_selector = selector.as_ref().map_or(false, |sel| matches_selector(sel, s, groups));
+            by_id || by_tag || by_selector
+        })
+        .cloned()
+        .collect())
+}
+
+// ✅ COMANDO: Esegue un comando su un gruppo di server
+#[command]
+pub async fn broadcast_command(app: AppHandle, request: BroadcastRequest) -> Result<BroadcastSummary, String> {
+    if request.command.trim().is_empty() {
+        return Err("Comando vuoto".to_string());
+    }
+
+    let servers = load_servers(app.clone()).await?;
+    let groups = load_groups(&app)?;
+    let targets = select_servers(
+        &servers,
+        &groups,
+        request.server_ids.as_deref(),
+        request.tag.as_deref(),
+        request.selector.as_deref(),
+    )?;
+    if targets.is_empty() {
+        return Err("Nessun server corrisponde alla selezione".to_string());
+    }
+
+    let broadcast_id = request.broadcast_id.clone().unwrap_or_else(|| new_id("broadcast"));
+    let parallelism = request.parallelism.unwrap_or(DEFAULT_PARALLELISM).max(1);
+    let fail_fast = request.fail_fast.unwrap_or(false);
+    let base_options = request.options.clone().unwrap_or_default();
+
+    println!("📡 Broadcast {} su {} server (parallelismo {}): {}", broadcast_id, targets.len(), parallelism, request.command);
+
+    let start = Instant::now();
+    let semaphore = Arc::new(Semaphore::new(parallelism));
+    let abort = Arc::new(AtomicBool::new(false));
+    let mut tasks = Vec::new();
+
+    for server in targets {
+        let app = app.clone();
+        let servers = servers.clone();
+        let semaphore = semaphore.clone();
+        let abort = abort.clone();
+        let command = request.command.clone();
+        let mut options = base_options.clone();
+        options.execution_id = Some(format!("{}:{}", broadcast_id, server.id));
+        options.stream = Some(options.stream.unwrap_or(true));
+
+        tasks.push(tokio::spawn(async move {
+            let _permit = semaphore.acquire_owned().await.ok();
+
+            if abort.load(Ordering::Relaxed) {
+                return host_result(&server, None, None, true);
+            }
+
+            let result = match execute_on_server(&app, server.clone(), servers, command, options).await {
+                Ok(output) => {
+                    let error = if output.connection_lost {
+                        Some("Connessione persa durante l'esecuzione".to_string())
+                    } else {
+                        None
+                    };
+                    host_result(&server, Some(output), error, false)
+                }
+                Err(e) => host_result(&server, None, Some(e), false),
+            };
+
+            if !result.success && fail_fast {
+                abort.store(true, Ordering::Relaxed);
+            }
+
+            let _ = app.emit("broadcast_host_done", result.clone());
+            result
+        }));
+    }
+
+    let mut results = Vec::new();
+    for task in tasks {
+        results.push(task.await.map_err(|e| format!("Errore task broadcast: {}", e))?);
+    }
+
+    let succeeded = results.iter().filter(|r| r.success).count();
+    let skipped = results.iter().filter(|r| r.skipped).count();
+
+    Ok(BroadcastSummary {
+        broadcast_id,
+        command: request.command,
+        total: results.len(),
+        succeeded,
+        failed: results.len() - succeeded - skipped,
+        skipped,
+        aborted: abort.load(Ordering::Relaxed),
+        duration_ms: start.elapsed().as_millis() as u64,
+        results,
+    })
+}
+
+fn host_result(
+    server: &Server,
+    output: Option<crate::remote_exec::RemoteCommandResult>,
+    error: Option<String>,
+    skipped: bool,
+) -> BroadcastHostResult {
+    let success = error.is_none() && output.as_ref().map_or(false, |o| o.success());
+
+    BroadcastHostResult {
+        server_id: server.id.clone(),
+        server_name: server.name.clone(),
+        ip: server.ip.clone(),
+        success,
+        exit_code: output.as_ref().and_then(|o| o.exit_code),
+        duration_ms: output.as_ref().map_or(0, |o| o.duration_ms),
+        timed_out: output.as_ref().map_or(false, |o| o.timed_out),
+        skipped,
+        error,
+        stdout: output.as_ref().map(|o| o.stdout.clone()).unwrap_or_default(),
+        stderr: output.map(|o| o.stderr).unwrap_or_default(),
+    }
+}
@@ -0,0 +1,252 @@
+// src-tauri/src/docker.rs
+// Gestione container Docker sui server remoti tramite il livello SSH
+// Gli errori tipici (docker assente, socket non accessibile...) hanno un `kind` distinto
+
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Emitter};
+
+use crate::remote_exec::{execute_on_server, shell_quote, spawn_follow, ExecOptions, RemoteCommandResult};
+use crate::ssh_session::find_server;
+use crate::new_id;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerError {
+    pub kind: String,       // "not_installed" | "permission_denied" | "daemon_unavailable" | "not_found" | "connection" | "invalid_request" | "command_failed"
+    pub message: String,
+}
+
+impl DockerError {
+    fn new(kind: &str, message: impl Into<String>) -> Self {
+        Self { kind: kind.to_string(), message: message.into() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "PascalCase"))]
+pub struct DockerContainer {
+    #[serde(rename(deserialize = "ID"), default)]
+    pub id: String,
+    #[serde(default)]
+    pub names: String,
+    #[serde(default)]
+    pub image: String,
+    #[serde(default)]
+    pub command: String,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub running_for: String,
+    #[serde(default)]
+    pub ports: String,
+    #[serde(default)]
+    pub state: String,
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub networks: String,
+    #[serde(default)]
+    pub labels: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "PascalCase"))]
+pub struct DockerStats {
+    #[serde(rename(deserialize = "ID"), default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(rename(deserialize = "CPUPerc"), default)]
+    pub cpu_percent: String,
+    #[serde(default)]
+    pub mem_usage: String,
+    #[serde(rename(deserialize = "MemPerc"), default)]
+    pub mem_percent: String,
+    #[serde(rename(deserialize = "NetIO"), default)]
+    pub net_io: String,
+    #[serde(rename(deserialize = "BlockIO"), default)]
+    pub block_io: String,
+    #[serde(rename(deserialize = "PIDs"), default)]
+    pub pids: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerLogLine {
+    pub stream_id: String,
+    pub server_id: String,
+    pub container: String,
+    pub stream: String,
+    pub line: String,
+}
+
+// Classifica l'errore leggendo exit code e stderr del client docker
+fn classify_failure(output: &RemoteCommandResult) -> DockerError {
+    let stderr = output.stderr.trim();
+    let lower = stderr.to_lowercase();
+
+    if output.timed_out {
+        DockerError::new("command_failed", "Timeout del comando docker")
+    } else if output.exit_code == Some(127) || lower.contains("command not found") || lower.contains("docker: not found") {
+        DockerError::new("not_installed", "Docker non è installato sul server")
+    } else if lower.contains("permission denied") && lower.contains("docker.sock") {
+        DockerError::new("permission_denied", "Permesso negato sul socket Docker (aggiungi l'utente al gruppo docker)")
+    } else if lower.contains("cannot connect to the docker daemon") || lower.contains("is the docker daemon running") {
+        DockerError::new("daemon_unavailable", "Il demone Docker non è in esecuzione")
+    } else if lower.contains("no such container") || lower.contains("no such object") {
+        DockerError::new("not_found", stderr)
+    } else {
+        DockerError::new("command_failed", if stderr.is_empty() { "Comando docker fallito" } else { stderr })
+    }
+}
+
+async fn run_docker(app: &AppHandle, server_id: &str, command: String) -> Result<String, DockerError> {
+    let (server, servers) = find_server(app, server_id)
+        .await
+        .map_err(|e| DockerError::new("connection", e))?;
+
+    let options = ExecOptions {
+        timeout_secs: Some(60),
+        ..Default::default()
+    };
+    let output = execute_on_server(app, server, servers, command, options)
+        .await
+        .map_err(|e| DockerError::new("connection", e))?;
+
+    if output.success() {
+        Ok(output.stdout)
+    } else {
+        Err(classify_failure(&output))
+    }
+}
+
+fn container_arg(container: &str) -> Result<String, DockerError> {
+    if container.trim().is_empty() {
+        return Err(DockerError::new("invalid_request", "Container non specificato"));
+    }
+    Ok(shell_quote(container.trim()))
+}
+
+// Una riga JSON per container (`--format '{{json .}}'`, compatibile anche con Docker datati)
+fn parse_json_lines<T: for<'de> Deserialize<'de>>(stdout: &str) -> Result<Vec<T>, DockerError> {
+    stdout
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| {
+            serde_json::from_str(l)
+                .map_err(|e| DockerError::new("command_failed", format!("Output docker non valido: {}", e)))
+        })
+        .collect()
+}
+
+// ✅ COMANDO: Elenco container
+#[command]
+pub async fn docker_list_containers(
+    app: AppHandle,
+    server_id: String,
+    all: Option<bool>,
+) -> Result<Vec<DockerContainer>, DockerError> {
+    let all_flag = if all.unwrap_or(true) { " -a" } else { "" };
+    let stdout = run_docker(&app, &server_id, format!("docker ps{} --no-trunc --format '{{{{json .}}}}'", all_flag)).await?;
+    parse_json_lines(&stdout)
+}
+
+// ✅ COMANDO: docker inspect (JSON completo)
+#[command]
+pub async fn docker_inspect_container(
+    app: AppHandle,
+    server_id: String,
+    container: String,
+) -> Result<serde_json::Value, DockerError> {
+    let stdout = run_docker(&app, &server_id, format!("docker inspect {}", container_arg(&container)?)).await?;
+
+    let mut parsed: Vec<serde_json::Value> = serde_json::from_str(&stdout)
+        .map_err(|e| DockerError::new("command_failed", format!("Output docker inspect non valido: {}", e)))?;
+    if parsed.is_empty() {
+        return Err(DockerError::new("not_found", format!("Container {} non trovato", container)));
+    }
+    Ok(parsed.remove(0))
+}
+
+// ✅ COMANDO: start / stop / restart / pause / unpause / kill / rm
+#[command]
+pub async fn docker_container_action(
+    app: AppHandle,
+    server_id: String,
+    container: String,
+    action: String,
+) -> Result<String, DockerError> {
+    let allowed = ["start", "stop", "restart", "pause", "unpause", "kill", "rm"];
+    if !allowed.contains(&action.as_str()) {
+        return Err(DockerError::new("invalid_request", format!("Azione non supportata: {}", action)));
+    }
+
+    println!("🐳 docker {} {} su {}", action, container, server_id);
+    let stdout = run_docker(&app, &server_id, format!("docker {} {}", action, container_arg(&container)?)).await?;
+    Ok(stdout.trim().to_string())
+}
+
+// ✅ COMANDO: Statistiche istantanee (tutti i container o uno solo)
+#[command]
+pub async fn docker_container_stats(
+    app: AppHandle,
+    server_id: String,
+    container: Option<String>,
+) -> Result<Vec<DockerStats>, DockerError> {
+    let target = match container.as_deref() {
+        Some(c) => format!(" {}", container_arg(c)?),
+        None => String::new(),
+    };
+    let stdout = run_docker(&app, &server_id, format!("docker stats --no-stream --format '{{{{json .}}}}'{}", target)).await?;
+    parse_json_lines(&stdout)
+}
+
+// ✅ COMANDO: Segue i log di un container (evento "docker_log_line")
+// Ritorna lo stream id da passare a stop_remote_stream
+#[command]
+pub async fn docker_follow_logs(
+    app: AppHandle,
+    server_id: String,
+    container: String,
+    tail: Option<u32>,
+) -> Result<String, DockerError> {
+    let (server, servers) = find_server(&app, &server_id)
+        .await
+        .map_err(|e| DockerError::new("connection", e))?;
+
+    let command = format!(
+        "docker logs --follow --timestamps --tail {} {}",
+        tail.unwrap_or(200),
+        container_arg(&container)?
+    );
+    let stream_id = new_id("docker-logs");
+
+    let app_lines = app.clone();
+    let app_end = app.clone();
+    let (line_stream_id, end_stream_id) = (stream_id.clone(), stream_id.clone());
+    let (line_container, line_server_id) = (container.clone(), server_id.clone());
+
+    spawn_follow(
+        server,
+        servers,
+        command,
+        stream_id.clone(),
+        move |stream, line| {
+            let _ = app_lines.emit("docker_log_line", DockerLogLine {
+                stream_id: line_stream_id.clone(),
+                server_id: line_server_id.clone(),
+                container: line_container.clone(),
+                stream: stream.to_string(),
+                line: line.to_string(),
+            });
+        },
+        move |error| {
+            let _ = app_end.emit("docker_log_end", serde_json::json!({
+                "streamId": end_stream_id,
+                "error": error,
+            }));
+        },
+    );
+
+    Ok(stream_id)
+}
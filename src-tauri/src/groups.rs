@@ -0,0 +1,560 @@
+// src-tauri/src/groups.rs
+// Tag dei server e gruppi annidati (cartelle) salvati in groups.json
+// Selettori per le operazioni di massa: "tag:web & group:prod", "group:dc1/rack2 | id:abc", "!tag:test"
+// Valori con spazi o operatori tra virgolette: group:"Data Center/Rack 2"
+
+use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle};
+
+use crate::snapshots::snapshot_before_change;
+use crate::{load_servers, new_id, read_json_file, store_servers, write_json_file, Server};
+
+const GROUPS_FILE: &str = "groups.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerGroup {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    #[serde(default)]
+    pub server_ids: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+// ---------------------------------------------------------------------------
+// Selettori
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Selector {
+    All,
+    Tag(String),
+    Group(String),
+    Id(String),
+    Name(String),
+    Type(String),
+    Not(Box<Selector>),
+    And(Box<Selector>, Box<Selector>),
+    Or(Box<Selector>, Box<Selector>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;             // la parola contiene una parte tra virgolette: mai una parola chiave
+
+    let flush = |word: &mut String, quoted: &mut bool, tokens: &mut Vec<Token>| {
+        if word.is_empty() && !*quoted {
+            return;
+        }
+        tokens.push(match word.to_lowercase().as_str() {
+            "and" if !*quoted => Token::And,
+            "or" if !*quoted => Token::Or,
+            "not" if !*quoted => Token::Not,
+            _ => Token::Word(word.clone()),
+        });
+        word.clear();
+        *quoted = false;
+    };
+
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            '"' | '\'' => {
+                // Tutto fino alla virgoletta di chiusura fa parte della parola
+                let closing = c;
+                loop {
+                    match chars.next() {
+                        Some(c) if c == closing => break,
+                        Some(c) => word.push(c),
+                        None => return Err("Virgolette non chiuse nel selettore".to_string()),
+                    }
+                }
+                quoted = true;
+                continue;
+            }
+            '&' | ',' => Some(Token::And),
+            '|' => Some(Token::Or),
+            '!' => Some(Token::Not),
+            '(' => Some(Token::Open),
+            ')' => Some(Token::Close),
+            c if c.is_whitespace() => None,
+            c => {
+                word.push(c);
+                continue;
+            }
+        };
+        flush(&mut word, &mut quoted, &mut tokens);
+        tokens.extend(token);
+    }
+    flush(&mut word, &mut quoted, &mut tokens);
+
+    Ok(tokens)
+}
+
+struct SelectorParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl SelectorParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    // or := and ("|" and)*
+    fn parse_or(&mut self) -> Result<Selector, String> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            left = Selector::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    // and := unary (("&")? unary)*   — due termini affiancati valgono come AND
+    fn parse_and(&mut self) -> Result<Selector, String> {
+        let mut left = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => self.pos += 1,
+                Some(Token::Word(_)) | Some(Token::Not) | Some(Token::Open) => {}
+                _ => break,
+            }
+            left = Selector::And(Box::new(left), Box::new(self.parse_unary()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Selector, String> {
+        match self.next() {
+            Some(Token::Not) => Ok(Selector::Not(Box::new(self.parse_unary()?))),
+            Some(Token::Open) => {
+                let inner = self.parse_or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(inner),
+                    _ => Err("Parentesi non chiusa nel selettore".to_string()),
+                }
+            }
+            Some(Token::Word(word)) => parse_term(&word),
+            Some(other) => Err(format!("Token inatteso nel selettore: {:?}", other)),
+            None => Err("Selettore incompleto".to_string()),
+        }
+    }
+}
+
+fn parse_term(word: &str) -> Result<Selector, String> {
+    if word == "*" || word.eq_ignore_ascii_case("all") {
+        return Ok(Selector::All);
+    }
+
+    let (key, value) = word.split_once(':').unwrap_or(("tag", word));
+    if value.is_empty() {
+        return Err(format!("Valore mancante nel selettore: {}", word));
+    }
+    let value = value.to_string();
+
+    match key.to_lowercase().as_str() {
+        "tag" => Ok(Selector::Tag(value)),
+        "group" => Ok(Selector::Group(value)),
+        "id" => Ok(Selector::Id(value)),
+        "name" => Ok(Selector::Name(value)),
+        "type" => Ok(Selector::Type(value)),
+        other => Err(format!("Chiave di selettore sconosciuta: {}", other)),
+    }
+}
+
+// ✅ Parsing di un'espressione di selezione
+pub fn parse_selector(input: &str) -> Result<Selector, String> {
+    let mut parser = SelectorParser { tokens: tokenize(input)?, pos: 0 };
+    if parser.tokens.is_empty() {
+        return Err("Selettore vuoto".to_string());
+    }
+
+    let selector = parser.parse_or()?;
+    if parser.pos < parser.tokens.len() {
+        return Err("Testo inatteso alla fine del selettore".to_string());
+    }
+    Ok(selector)
+}
+
+// Confronto case-insensitive con "*" come jolly
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let text = text.to_lowercase();
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+
+    let mut rest = text.as_str();
+    for (i, part) in parts.iter().enumerate() {
+        if i == 0 {
+            match rest.strip_prefix(part) {
+                Some(r) => rest = r,
+                None => return false,
+            }
+        } else if i == parts.len() - 1 {
+            return rest.ends_with(part);
+        } else {
+            match rest.find(part) {
+                Some(pos) => rest = &rest[pos + part.len()..],
+                None => return false,
+            }
+        }
+    }
+    true
+}
+
+// Percorso "padre/figlio" di un gruppo (con protezione dai cicli)
+pub(crate) fn group_path(group: &ServerGroup, groups: &[ServerGroup]) -> String {
+    let mut names = vec![group.name.clone()];
+    let mut current = group.parent_id.clone();
+    let mut seen = HashSet::new();
+
+    while let Some(parent_id) = current {
+        if !seen.insert(parent_id.clone()) {
+            break;
+        }
+        match groups.iter().find(|g| g.id == parent_id) {
+            Some(parent) => {
+                names.push(parent.name.clone());
+                current = parent.parent_id.clone();
+            }
+            None => break,
+        }
+    }
+
+    names.reverse();
+    names.join("/")
+}
+
+// Id del gruppo e di tutti i suoi discendenti
+fn group_with_descendants(root_id: &str, groups: &[ServerGroup]) -> HashSet<String> {
+    let mut ids: HashSet<String> = HashSet::from([root_id.to_string()]);
+    loop {
+        let before = ids.len();
+        for group in groups {
+            if group.parent_id.as_ref().map_or(false, |p| ids.contains(p)) {
+                ids.insert(group.id.clone());
+            }
+        }
+        if ids.len() == before {
+            return ids;
+        }
+    }
+}
+
+fn in_group(server: &Server, pattern: &str, groups: &[ServerGroup]) -> bool {
+    groups
+        .iter()
+        .filter(|g| g.id == pattern || glob_match(pattern, &g.name) || glob_match(pattern, &group_path(g, groups)))
+        .any(|root| {
+            let ids = group_with_descendants(&root.id, groups);
+            groups.iter().any(|g| ids.contains(&g.id) && g.server_ids.contains(&server.id))
+        })
+}
+
+pub fn matches_selector(selector: &Selector, server: &Server, groups: &[ServerGroup]) -> bool {
+    match selector {
+        Selector::All => true,
+        Selector::Tag(tag) => server.tags.iter().any(|t| glob_match(tag, t)),
+        Selector::Group(group) => in_group(server, group, groups),
+        Selector::Id(id) => &server.id == id,
+        Selector::Name(name) => glob_match(name, &server.name),
+        Selector::Type(server_type) => glob_match(server_type, &server.server_type),
+        Selector::Not(inner) => !matches_selector(inner, server, groups),
+        Selector::And(a, b) => matches_selector(a, server, groups) && matches_selector(b, server, groups),
+        Selector::Or(a, b) => matches_selector(a, server, groups) || matches_selector(b, server, groups),
+    }
+}
+
+// ✅ Server che soddisfano il selettore, nell'ordine della lista salvata
+pub fn filter_by_selector(servers: &[Server], groups: &[ServerGroup], selector: &str) -> Result<Vec<Server>, String> {
+    let selector = parse_selector(selector)?;
+    Ok(servers
+        .iter()
+        .filter(|s| matches_selector(&selector, s, groups))
+        .cloned()
+        .collect())
+}
+
+pub async fn resolve_selector(app: &AppHandle, selector: &str) -> Result<Vec<Server>, String> {
+    let servers = load_servers(app.clone()).await?;
+    filter_by_selector(&servers, &load_groups(app)?, selector)
+}
+
+pub fn load_groups(app: &AppHandle) -> Result<Vec<ServerGroup>, String> {
+    read_json_file(app, GROUPS_FILE)
+}
+
+// ---------------------------------------------------------------------------
+// Comandi
+// ---------------------------------------------------------------------------
+
+// ✅ COMANDO: Elenco gruppi (piatto, la gerarchia è data da parentId)
+#[command]
+pub async fn list_server_groups(app: AppHandle) -> Result<Vec<ServerGroup>, String> {
+    load_groups(&app)
+}
+
+// ✅ COMANDO: Crea o rinomina/sposta un gruppo (i membri si gestiscono con assign/unassign)
+#[command]
+pub async fn save_server_group(app: AppHandle, mut group: ServerGroup) -> Result<ServerGroup, String> {
+    if group.name.trim().is_empty() {
+        return Err("Il nome del gruppo è obbligatorio".to_string());
+    }
+    if group.name.contains('/') {
+        return Err("Il nome del gruppo non può contenere '/'".to_string());
+    }
+
+    let mut groups = load_groups(&app)?;
+    if group.id.is_empty() {
+        group.id = new_id("group");
+    }
+
+    if let Some(parent_id) = &group.parent_id {
+        if !groups.iter().any(|g| &g.id == parent_id) {
+            return Err(format!("Gruppo padre '{}' non trovato", parent_id));
+        }
+        // Il nuovo padre non può essere il gruppo stesso né un suo discendente
+        if group_with_descendants(&group.id, &groups).contains(parent_id) {
+            return Err("Un gruppo non può essere spostato dentro sé stesso".to_string());
+        }
+    }
+
+    match groups.iter_mut().find(|g| g.id == group.id) {
+        Some(existing) => {
+            existing.name = group.name.clone();
+            existing.parent_id = group.parent_id.clone();
+            group.server_ids = existing.server_ids.clone();
+        }
+        None => groups.push(group.clone()),
+    }
+
+    snapshot_before_change(&app, "salvataggio gruppo")?;
+    write_json_file(&app, GROUPS_FILE, &groups)?;
+    Ok(group)
+}
+
+// ✅ COMANDO: Elimina un gruppo; i sottogruppi risalgono al padre
+#[command]
+pub async fn delete_server_group(app: AppHandle, group_id: String) -> Result<(), String> {
+    let mut groups = load_groups(&app)?;
+    let removed = groups
+        .iter()
+        .position(|g| g.id == group_id)
+        .map(|i| groups.remove(i))
+        .ok_or_else(|| format!("Gruppo '{}' non trovato", group_id))?;
+
+    for group in groups.iter_mut().filter(|g| g.parent_id.as_deref() == Some(group_id.as_str())) {
+        group.parent_id = removed.parent_id.clone();
+    }
+    snapshot_before_change(&app, "eliminazione gruppo")?;
+    write_json_file(&app, GROUPS_FILE, &groups)
+}
+
+// ✅ COMANDO: Aggiunge server a un gruppo
+#[command]
+pub async fn assign_servers_to_group(app: AppHandle, group_id: String, server_ids: Vec<String>) -> Result<ServerGroup, String> {
+    let known: HashSet<String> = load_servers(app.clone()).await?.into_iter().map(|s| s.id).collect();
+    if let Some(unknown) = server_ids.iter().find(|id| !known.contains(*id)) {
+        return Err(format!("Server '{}' non trovato", unknown));
+    }
+
+    let mut groups = load_groups(&app)?;
+    let group = groups
+        .iter_mut()
+        .find(|g| g.id == group_id)
+        .ok_or_else(|| format!("Gruppo '{}' non trovato", group_id))?;
+
+    for id in server_ids {
+        if !group.server_ids.contains(&id) {
+            group.server_ids.push(id);
+        }
+    }
+    let group = group.clone();
+
+    snapshot_before_change(&app, "assegnazione server al gruppo")?;
+    write_json_file(&app, GROUPS_FILE, &groups)?;
+    Ok(group)
+}
+
+// ✅ COMANDO: Rimuove server da un gruppo
+#[command]
+pub async fn unassign_servers_from_group(app: AppHandle, group_id: String, server_ids: Vec<String>) -> Result<ServerGroup, String> {
+    let mut groups = load_groups(&app)?;
+    let group = groups
+        .iter_mut()
+        .find(|g| g.id == group_id)
+        .ok_or_else(|| format!("Gruppo '{}' non trovato", group_id))?;
+
+    group.server_ids.retain(|id| !server_ids.contains(id));
+    let group = group.clone();
+
+    snapshot_before_change(&app, "rimozione server dal gruppo")?;
+    write_json_file(&app, GROUPS_FILE, &groups)?;
+    Ok(group)
+}
+
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        if !normalized.iter().any(|n| n.eq_ignore_ascii_case(tag)) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+// ✅ COMANDO: Aggiunge tag a uno o più server
+#[command]
+pub async fn add_server_tags(app: AppHandle, server_ids: Vec<String>, tags: Vec<String>) -> Result<usize, String> {
+    let tags = normalize_tags(&tags);
+    if tags.iter().any(|t| t.chars().any(|c| c.is_whitespace() || "&|!(),:".contains(c))) {
+        return Err("I tag non possono contenere spazi o i caratteri & | ! ( ) , :".to_string());
+    }
+
+    let mut servers = load_servers(app.clone()).await?;
+    let mut updated = 0;
+    for server in servers.iter_mut().filter(|s| server_ids.contains(&s.id)) {
+        let merged: Vec<String> = server.tags.iter().cloned().chain(tags.iter().cloned()).collect();
+        server.tags = normalize_tags(&merged);
+        updated += 1;
+    }
+
+    store_servers(&app, &servers)?;
+    Ok(updated)
+}
+
+// ✅ COMANDO: Rimuove tag da uno o più server
+#[command]
+pub async fn remove_server_tags(app: AppHandle, server_ids: Vec<String>, tags: Vec<String>) -> Result<usize, String> {
+    let mut servers = load_servers(app.clone()).await?;
+    let mut updated = 0;
+    for server in servers.iter_mut().filter(|s| server_ids.contains(&s.id)) {
+        server.tags.retain(|t| !tags.iter().any(|r| r.trim().eq_ignore_ascii_case(t)));
+        updated += 1;
+    }
+
+    store_servers(&app, &servers)?;
+    Ok(updated)
+}
+
+// ✅ COMANDO: Tutti i tag in uso con il numero di server
+#[command]
+pub async fn list_server_tags(app: AppHandle) -> Result<Vec<TagCount>, String> {
+    let servers = load_servers(app).await?;
+    let mut counts: Vec<TagCount> = Vec::new();
+
+    for tag in servers.iter().flat_map(|s| s.tags.iter()) {
+        match counts.iter_mut().find(|c| c.tag.eq_ignore_ascii_case(tag)) {
+            Some(count) => count.count += 1,
+            None => counts.push(TagCount { tag: tag.clone(), count: 1 }),
+        }
+    }
+
+    counts.sort_by(|a, b| a.tag.to_lowercase().cmp(&b.tag.to_lowercase()));
+    Ok(counts)
+}
+
+// ✅ COMANDO: Server che soddisfano un selettore
+#[command]
+pub async fn query_servers(app: AppHandle, selector: String) -> Result<Vec<Server>, String> {
+    resolve_selector(&app, &selector).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(value: &str) -> Box<Selector> {
+        Box::new(Selector::Tag(value.to_string()))
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let selector = parse_selector("tag:web & group:prod | id:abc").unwrap();
+        assert_eq!(
+            selector,
+            Selector::Or(
+                Box::new(Selector::And(tag("web"), Box::new(Selector::Group("prod".to_string())))),
+                Box::new(Selector::Id("abc".to_string())),
+            )
+        );
+    }
+
+    #[test]
+    fn keywords_negation_and_juxtaposition() {
+        assert_eq!(
+            parse_selector("web not test").unwrap(),
+            Selector::And(tag("web"), Box::new(Selector::Not(tag("test"))))
+        );
+        assert_eq!(
+            parse_selector("!(web or db)").unwrap(),
+            Selector::Not(Box::new(Selector::Or(tag("web"), tag("db"))))
+        );
+        assert_eq!(parse_selector("*").unwrap(), Selector::All);
+    }
+
+    #[test]
+    fn quoted_values_keep_spaces_and_operators() {
+        assert_eq!(
+            parse_selector(r#"group:"Data Center/Rack 2""#).unwrap(),
+            Selector::Group("Data Center/Rack 2".to_string())
+        );
+        assert_eq!(parse_selector("tag:'a|b'").unwrap(), Selector::Tag("a|b".to_string()));
+        assert_eq!(parse_selector(r#""and""#).unwrap(), Selector::Tag("and".to_string()));
+    }
+
+    #[test]
+    fn rejects_malformed_selectors() {
+        for input in ["", "(web", "web |", "color:red", "tag:", "group:\"open", "web )"] {
+            assert!(parse_selector(input).is_err(), "{} dovrebbe essere rifiutato", input);
+        }
+    }
+
+    #[test]
+    fn group_selector_includes_nested_groups() {
+        let groups = vec![
+            ServerGroup { id: "g1".to_string(), name: "dc1".to_string(), parent_id: None, server_ids: vec![] },
+            ServerGroup {
+                id: "g2".to_string(),
+                name: "rack2".to_string(),
+                parent_id: Some("g1".to_string()),
+                server_ids: vec!["s1".to_string()],
+            },
+        ];
+        let server = Server { id: "s1".to_string(), ..Default::default() };
+
+        assert!(matches_selector(&parse_selector("group:dc1").unwrap(), &server, &groups));
+        assert!(matches_selector(&parse_selector("group:dc1/rack2").unwrap(), &server, &groups));
+        assert!(!matches_selector(&parse_selector("!group:dc1").unwrap(), &server, &groups));
+    }
+}
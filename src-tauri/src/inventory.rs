@@ -0,0 +1,313 @@
+// src-tauri/src/inventory.rs
+// Inventario agentless di OS e hardware dei server remoti
+// Snapshot salvati in inventory.json (per server, con timestamp)
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle};
+
+use crate::remote_exec::{execute_on_server, split_output_sections, ExecOptions};
+use crate::ssh_session::find_server;
+use crate::{load_servers, read_json_file, store_servers, write_json_file};
+
+const INVENTORY_FILE: &str = "inventory.json";
+
+const INVENTORY_SCRIPT: &str = r#"echo ==UNAME==; uname -s; uname -r; uname -m
+echo ==HOSTNAME==; hostname
+echo ==OSRELEASE==; cat /etc/os-release 2>/dev/null
+echo ==SWVERS==; sw_vers 2>/dev/null
+echo ==CPUMODEL==; (grep -m 1 'model name' /proc/cpuinfo 2>/dev/null || sysctl -n machdep.cpu.brand_string 2>/dev/null) | sed 's/^model name[[:space:]]*:[[:space:]]*//'
+echo ==CPUCORES==; nproc 2>/dev/null || sysctl -n hw.ncpu 2>/dev/null
+echo ==MEMTOTAL==; awk '/MemTotal/ {print $2 * 1024}' /proc/meminfo 2>/dev/null || sysctl -n hw.memsize 2>/dev/null
+echo ==LSBLK==; lsblk -dbn -o NAME,SIZE,TYPE,MODEL 2>/dev/null
+echo ==IPLINK==; ip -o link 2>/dev/null
+echo ==IPADDR==; ip -o addr 2>/dev/null
+echo ==IFCONFIG==; command -v ip >/dev/null 2>&1 || ifconfig 2>/dev/null
+echo ==DEFROUTE==; { ip route get 1 2>/dev/null || route -n get default 2>/dev/null; } | sed -n -e 's/.* dev \([^ ]*\).*/\1/p' -e 's/.*interface: *//p'
+echo ==VIRT==; systemd-detect-virt 2>/dev/null || cat /sys/class/dmi/id/product_name 2>/dev/null || sysctl -n kern.hv_vmm_present 2>/dev/null"#;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryDisk {
+    pub name: String,
+    pub size_bytes: u64,
+    pub model: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkInterface {
+    pub name: String,
+    pub mac_address: Option<String>,
+    pub addresses: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerInventory {
+    pub server_id: String,
+    pub collected_at: String,
+    pub hostname: String,
+    pub os_name: String,
+    pub os_version: String,
+    pub kernel: String,
+    pub architecture: String,
+    pub cpu_model: Option<String>,
+    pub cpu_cores: Option<u32>,
+    pub memory_total_bytes: Option<u64>,
+    pub disks: Vec<InventoryDisk>,
+    pub network_interfaces: Vec<NetworkInterface>,
+    pub default_interface: Option<String>,   // interfaccia della rotta di default
+    pub virtualization: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryResult {
+    pub inventory: ServerInventory,
+    pub mac_address_filled: Option<String>,
+}
+
+// ✅ COMANDO: Rileva OS e hardware del server e salva lo snapshot
+#[command]
+pub async fn collect_server_inventory(
+    app: AppHandle,
+    server_id: String,
+    auto_fill_mac: Option<bool>,
+) -> Result<InventoryResult, String> {
+    let (server, servers) = find_server(&app, &server_id).await?;
+    let options = ExecOptions {
+        timeout_secs: Some(30),
+        ..Default::default()
+    };
+
+    println!("🧾 Inventario di {} ({})", server.name, server.ip);
+    let output = execute_on_server(&app, server.clone(), servers, INVENTORY_SCRIPT.to_string(), options).await?;
+    if output.timed_out {
+        return Err("Timeout durante l'inventario".to_string());
+    }
+
+    let mut inventory = parse_inventory_output(&output.stdout);
+    inventory.server_id = server_id.clone();
+    inventory.collected_at = chrono::Local::now().to_rfc3339();
+
+    let mut snapshots: HashMap<String, ServerInventory> = read_json_file(&app, INVENTORY_FILE)?;
+    snapshots.insert(server_id.clone(), inventory.clone());
+    write_json_file(&app, INVENTORY_FILE, &snapshots)?;
+
+    // 🆕 Auto-compilazione MAC per Wake-on-LAN (solo se non già impostato)
+    let mut mac_address_filled = None;
+    if auto_fill_mac.unwrap_or(true) && server.mac_address.as_deref().map_or(true, |m| m.trim().is_empty()) {
+        let host = server.ip.clone();
+        let addresses = tokio::task::spawn_blocking(move || resolve_addresses(&host))
+            .await
+            .unwrap_or_default();
+        if let Some(mac) = primary_mac(&inventory, &addresses) {
+            let mut servers = load_servers(app.clone()).await?;
+            if let Some(saved) = servers.iter_mut().find(|s| s.id == server_id) {
+                saved.mac_address = Some(mac.clone());
+                store_servers(&app, &servers)?;
+                println!("🔌 MAC {} impostato per {}", mac, server.name);
+                mac_address_filled = Some(mac);
+            }
+        }
+    }
+
+    Ok(InventoryResult { inventory, mac_address_filled })
+}
+
+// ✅ COMANDO: Ultimo snapshot salvato per un server
+#[command]
+pub async fn get_server_inventory(app: AppHandle, server_id: String) -> Result<Option<ServerInventory>, String> {
+    let snapshots: HashMap<String, ServerInventory> = read_json_file(&app, INVENTORY_FILE)?;
+    Ok(snapshots.get(&server_id).cloned())
+}
+
+// Indirizzi dell'host salvato: l'host stesso più la risoluzione DNS locale (server salvati per nome)
+fn resolve_addresses(host: &str) -> Vec<String> {
+    let mut addresses = vec![host.trim().to_string()];
+    if let Ok(resolved) = dns_lookup::lookup_host(host.trim()) {
+        addresses.extend(resolved.iter().map(|a| a.to_string()));
+    }
+    addresses
+}
+
+// MAC dell'interfaccia che possiede uno degli indirizzi usati per connettersi,
+// altrimenti dell'interfaccia della rotta di default (es. server dietro jump host)
+// Mai la prima interfaccia con MAC: può essere docker0, un bridge o una veth
+fn primary_mac(inventory: &ServerInventory, addresses: &[String]) -> Option<String> {
+    let interfaces = &inventory.network_interfaces;
+    interfaces
+        .iter()
+        .find(|iface| iface.mac_address.is_some() && iface.addresses.iter().any(|a| addresses.contains(a)))
+        .or_else(|| {
+            let default = inventory.default_interface.as_deref()?;
+            interfaces.iter().find(|iface| iface.name == default)
+        })
+        .and_then(|iface| iface.mac_address.clone())
+}
+
+// ✅ Parsing dell'output dello script di inventario
+pub fn parse_inventory_output(output: &str) -> ServerInventory {
+    let sections = split_output_sections(output);
+    let section = |name: &str| sections.get(name).map(|v| v.as_slice()).unwrap_or(&[]);
+    let line = |name: &str, index: usize| section(name).get(index).map(|s| s.trim().to_string()).unwrap_or_default();
+
+    let os_release: HashMap<String, String> = section("OSRELEASE")
+        .iter()
+        .filter_map(|l| l.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().trim_matches('"').to_string()))
+        .collect();
+    let sw_vers: HashMap<String, String> = section("SWVERS")
+        .iter()
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect();
+
+    let (os_name, os_version) = if let Some(name) = os_release.get("NAME") {
+        (name.clone(), os_release.get("VERSION").or(os_release.get("VERSION_ID")).cloned().unwrap_or_default())
+    } else if let Some(name) = sw_vers.get("ProductName") {
+        (name.clone(), sw_vers.get("ProductVersion").cloned().unwrap_or_default())
+    } else {
+        (line("UNAME", 0), String::new())
+    };
+
+    let network_interfaces = if section("IPLINK").is_empty() {
+        parse_ifconfig(section("IFCONFIG"))
+    } else {
+        parse_ip_link(section("IPLINK"), section("IPADDR"))
+    };
+
+    ServerInventory {
+        hostname: line("HOSTNAME", 0),
+        os_name,
+        os_version,
+        kernel: line("UNAME", 1),
+        architecture: line("UNAME", 2),
+        cpu_model: Some(line("CPUMODEL", 0)).filter(|m| !m.is_empty()),
+        cpu_cores: line("CPUCORES", 0).parse().ok(),
+        memory_total_bytes: line("MEMTOTAL", 0).parse::<f64>().ok().map(|v| v as u64),
+        disks: parse_lsblk(section("LSBLK")),
+        network_interfaces,
+        default_interface: Some(line("DEFROUTE", 0)).filter(|i| !i.is_empty()),
+        virtualization: parse_virtualization(&line("VIRT", 0)),
+        ..Default::default()
+    }
+}
+
+// "sda 500107862016 disk Samsung SSD 860" -> solo i dischi fisici
+fn parse_lsblk(lines: &[String]) -> Vec<InventoryDisk> {
+    lines
+        .iter()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 3 || fields[2] != "disk" {
+                return None;
+            }
+
+            Some(InventoryDisk {
+                name: fields[0].to_string(),
+                size_bytes: fields[1].parse().ok()?,
+                model: Some(fields[3..].join(" ")).filter(|m| !m.is_empty()),
+            })
+        })
+        .collect()
+}
+
+fn is_usable_mac(mac: &str) -> bool {
+    mac.len() == 17 && mac != "00:00:00:00:00:00"
+}
+
+// `ip -o link` + `ip -o addr` (Linux)
+fn parse_ip_link(links: &[String], addrs: &[String]) -> Vec<NetworkInterface> {
+    let mut interfaces: Vec<NetworkInterface> = links
+        .iter()
+        .filter_map(|line| {
+            let name = line.split(':').nth(1)?.trim().split('@').next()?.to_string();
+            if name == "lo" {
+                return None;
+            }
+
+            let mac = line
+                .split_whitespace()
+                .skip_while(|f| *f != "link/ether")
+                .nth(1)
+                .map(|m| m.to_uppercase())
+                .filter(|m| is_usable_mac(m));
+
+            Some(NetworkInterface { name, mac_address: mac, addresses: Vec::new() })
+        })
+        .collect();
+
+    for line in addrs {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 4 || !(fields[2] == "inet" || fields[2] == "inet6") {
+            continue;
+        }
+
+        let name = fields[1].split('@').next().unwrap_or(fields[1]);
+        let address = fields[3].split('/').next().unwrap_or(fields[3]).to_string();
+        if let Some(iface) = interfaces.iter_mut().find(|i| i.name == name) {
+            iface.addresses.push(address);
+        }
+    }
+
+    interfaces
+}
+
+// `ifconfig` (macOS / BSD)
+fn parse_ifconfig(lines: &[String]) -> Vec<NetworkInterface> {
+    let mut interfaces: Vec<NetworkInterface> = Vec::new();
+
+    for line in lines {
+        if !line.starts_with(char::is_whitespace) {
+            if let Some((name, _)) = line.split_once(':') {
+                interfaces.push(NetworkInterface { name: name.to_string(), ..Default::default() });
+            }
+            continue;
+        }
+
+        let Some(iface) = interfaces.last_mut() else { continue };
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            ["ether", mac, ..] => {
+                let mac = mac.to_uppercase();
+                if is_usable_mac(&mac) {
+                    iface.mac_address = Some(mac);
+                }
+            }
+            ["inet", addr, ..] | ["inet6", addr, ..] => {
+                iface.addresses.push(addr.split('%').next().unwrap_or(addr).to_string());
+            }
+            _ => {}
+        }
+    }
+
+    interfaces.retain(|i| !i.name.starts_with("lo"));
+    interfaces
+}
+
+fn parse_virtualization(value: &str) -> Option<String> {
+    let value = value.trim();
+    match value {
+        "" => None,
+        "none" | "0" => Some("bare-metal".to_string()),
+        "1" => Some("vm".to_string()),
+        other => {
+            let lower = other.to_lowercase();
+            // product_name DMI: riconosce gli hypervisor più comuni
+            let known = [
+                ("kvm", "kvm"), ("qemu", "qemu"), ("vmware", "vmware"), ("virtualbox", "oracle"),
+                ("hyper-v", "microsoft"), ("virtual machine", "microsoft"), ("xen", "xen"),
+                ("lxc", "lxc"), ("docker", "docker"), ("openvz", "openvz"), ("wsl", "wsl"),
+            ];
+
+            match known.iter().find(|(needle, _)| lower.contains(needle)) {
+                Some((_, name)) => Some(name.to_string()),
+                // systemd-detect-virt stampa un identificatore breve, DMI il nome del modello fisico
+                None if !lower.contains(' ') => Some(lower),
+                None => Some("bare-metal".to_string()),
+            }
+        }
+    }
+}
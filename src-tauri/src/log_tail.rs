@@ -0,0 +1,354 @@
+// src-tauri/src/log_tail.rs
+// Streaming dei log remoti: più file (tail -F) o journald per server, filtro grep lato server
+// Le righe arrivano al frontend a blocchi ("log_tail_lines") con backpressure basata su ack
+// e un tetto alle righe in coda; l'ultima finestra catturata si può salvare su file locale
+
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Emitter};
+
+use crate::remote_exec::{shell_quote, spawn_follow, stop_remote_stream};
+use crate::ssh_session::find_server;
+use crate::new_id;
+
+const DEFAULT_MAX_BUFFERED: usize = 5_000;
+const DEFAULT_INITIAL_LINES: u32 = 50;
+const BATCH_MAX_LINES: usize = 500;
+const FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+// Se il frontend non conferma un blocco entro questo tempo si invia comunque il successivo
+const ACK_GRACE: Duration = Duration::from_secs(2);
+// Finestre catturate dei tail terminati: restano per il salvataggio, ma non per sempre
+const CAPTURE_TTL: Duration = Duration::from_secs(30 * 60);
+const MAX_KEPT_CAPTURES: usize = 5;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSource {
+    pub kind: String,            // "file" | "journal"
+    pub path: Option<String>,    // per "file"
+    pub unit: Option<String>,    // per "journal" (opzionale: tutto il journal)
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFilter {
+    pub pattern: String,
+    #[serde(default)]
+    pub ignore_case: bool,
+    #[serde(default)]
+    pub invert: bool,
+    #[serde(default)]
+    pub fixed_string: bool,      // -F invece di -E
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LogTailRequest {
+    pub server_id: String,
+    pub sources: Vec<LogSource>,
+    pub filter: Option<LogFilter>,
+    pub initial_lines: Option<u32>,
+    pub max_buffered_lines: Option<usize>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLine {
+    pub source: String,
+    pub stream: String,          // "stdout" | "stderr"
+    pub line: String,
+    pub received_at: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogBatch {
+    pub tail_id: String,
+    pub server_id: String,
+    pub lines: Vec<LogLine>,
+    pub dropped: u64,            // righe scartate finora per coda piena
+}
+
+struct TailState {
+    server_id: String,
+    pending: VecDeque<LogLine>,  // non ancora inviate al frontend
+    capture: VecDeque<LogLine>,  // ultima finestra, per il salvataggio
+    max_buffered: usize,
+    dropped: u64,
+    awaiting_ack: bool,
+    last_emit: Option<Instant>,
+    active_streams: usize,
+    stream_ids: Vec<String>,
+    stop: Arc<AtomicBool>,
+    finished_at: Option<Instant>,
+}
+
+impl TailState {
+    fn push(&mut self, line: LogLine) {
+        if self.pending.len() >= self.max_buffered {
+            self.pending.pop_front();
+            self.dropped += 1;
+        }
+        if self.capture.len() >= self.max_buffered {
+            self.capture.pop_front();
+        }
+        self.capture.push_back(line.clone());
+        self.pending.push_back(line);
+    }
+}
+
+static LOG_TAILS: OnceCell<Mutex<HashMap<String, Arc<Mutex<TailState>>>>> = OnceCell::new();
+
+fn tails() -> &'static Mutex<HashMap<String, Arc<Mutex<TailState>>>> {
+    LOG_TAILS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn get_tail(tail_id: &str) -> Result<Arc<Mutex<TailState>>, String> {
+    tails()
+        .lock()
+        .unwrap()
+        .get(tail_id)
+        .cloned()
+        .ok_or_else(|| format!("Tail '{}' non trovato", tail_id))
+}
+
+// Rimuove i tail terminati da più di CAPTURE_TTL e, oltre MAX_KEPT_CAPTURES, i più vecchi
+fn prune_finished_tails() {
+    let mut tails = tails().lock().unwrap();
+    let mut finished: Vec<(String, Instant)> = tails
+        .iter()
+        .filter_map(|(id, state)| state.lock().unwrap().finished_at.map(|at| (id.clone(), at)))
+        .collect();
+    finished.sort_by_key(|(_, at)| std::cmp::Reverse(*at));
+
+    for (index, (id, at)) in finished.into_iter().enumerate() {
+        if index >= MAX_KEPT_CAPTURES || at.elapsed() >= CAPTURE_TTL {
+            tails.remove(&id);
+        }
+    }
+}
+
+// Comando remoto e etichetta leggibile per una sorgente
+fn source_command(source: &LogSource, initial_lines: u32, filter: Option<&LogFilter>) -> Result<(String, String), String> {
+    let (label, base) = match source.kind.as_str() {
+        "file" => {
+            let path = source.path.as_deref().map(str::trim).unwrap_or_default();
+            if path.is_empty() {
+                return Err("Percorso del file di log mancante".to_string());
+            }
+            (path.to_string(), format!("tail -n {} -F -- {}", initial_lines, shell_quote(path)))
+        }
+        "journal" => {
+            let unit = source.unit.as_deref().map(str::trim).filter(|u| !u.is_empty());
+            let unit_arg = unit.map(|u| format!(" -u {}", shell_quote(u))).unwrap_or_default();
+            (
+                format!("journal:{}", unit.unwrap_or("*")),
+                format!("journalctl -f -n {} --no-pager -o short-iso{}", initial_lines, unit_arg),
+            )
+        }
+        other => return Err(format!("Tipo di sorgente non supportato: {}", other)),
+    };
+
+    let command = match filter.filter(|f| !f.pattern.is_empty()) {
+        Some(f) => format!(
+            "{} | grep --line-buffered{}{} {} -e {}",
+            base,
+            if f.ignore_case { " -i" } else { "" },
+            if f.invert { " -v" } else { "" },
+            if f.fixed_string { "-F" } else { "-E" },
+            shell_quote(&f.pattern)
+        ),
+        None => base,
+    };
+
+    Ok((label, command))
+}
+
+// ✅ COMANDO: Avvia il follow di una o più sorgenti di log; ritorna il tail id
+#[command]
+pub async fn start_log_tail(app: AppHandle, request: LogTailRequest) -> Result<String, String> {
+    if request.sources.is_empty() {
+        return Err("Nessuna sorgente di log specificata".to_string());
+    }
+
+    let initial_lines = request.initial_lines.unwrap_or(DEFAULT_INITIAL_LINES);
+    let commands = request
+        .sources
+        .iter()
+        .map(|s| source_command(s, initial_lines, request.filter.as_ref()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (server, servers) = find_server(&app, &request.server_id).await?;
+    let tail_id = new_id("tail");
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let state = Arc::new(Mutex::new(TailState {
+        server_id: request.server_id.clone(),
+        pending: VecDeque::new(),
+        capture: VecDeque::new(),
+        max_buffered: request.max_buffered_lines.unwrap_or(DEFAULT_MAX_BUFFERED).max(100),
+        dropped: 0,
+        awaiting_ack: false,
+        last_emit: None,
+        active_streams: commands.len(),
+        stream_ids: Vec::new(),
+        stop: stop.clone(),
+        finished_at: None,
+    }));
+    prune_finished_tails();
+    tails().lock().unwrap().insert(tail_id.clone(), state.clone());
+
+    println!("📜 Tail {} su {}: {} sorgenti", tail_id, server.name, commands.len());
+
+    // Un follow SSH per sorgente, tutti confluiscono nella stessa coda
+    for (label, command) in commands {
+        let stream_id = new_id("tail-stream");
+        state.lock().unwrap().stream_ids.push(stream_id.clone());
+
+        let line_state = state.clone();
+        let end_state = state.clone();
+        let line_label = label.clone();
+
+        spawn_follow(
+            server.clone(),
+            servers.clone(),
+            command,
+            stream_id,
+            move |stream, line| {
+                line_state.lock().unwrap().push(LogLine {
+                    source: line_label.clone(),
+                    stream: stream.to_string(),
+                    line: line.to_string(),
+                    received_at: chrono::Local::now().to_rfc3339(),
+                });
+            },
+            move |error| {
+                let mut tail = end_state.lock().unwrap();
+                if let Some(error) = error {
+                    tail.push(LogLine {
+                        source: label,
+                        stream: "stderr".to_string(),
+                        line: format!("⚠️ Stream terminato: {}", error),
+                        received_at: chrono::Local::now().to_rfc3339(),
+                    });
+                }
+                tail.active_streams = tail.active_streams.saturating_sub(1);
+            },
+        );
+    }
+
+    spawn_emitter(app, tail_id.clone(), state, stop);
+    Ok(tail_id)
+}
+
+// Invia la coda a blocchi, aspettando l'ack del frontend (o ACK_GRACE) tra un blocco e l'altro
+fn spawn_emitter(app: AppHandle, tail_id: String, state: Arc<Mutex<TailState>>, stop: Arc<AtomicBool>) {
+    thread::spawn(move || loop {
+        thread::sleep(FLUSH_INTERVAL);
+
+        let (batch, finished) = {
+            let mut tail = state.lock().unwrap();
+            let can_send = !tail.awaiting_ack || tail.last_emit.map_or(true, |t| t.elapsed() >= ACK_GRACE);
+
+            let batch = if can_send && !tail.pending.is_empty() {
+                let count = tail.pending.len().min(BATCH_MAX_LINES);
+                let lines: Vec<LogLine> = tail.pending.drain(..count).collect();
+                tail.awaiting_ack = true;
+                tail.last_emit = Some(Instant::now());
+                Some(LogBatch {
+                    tail_id: tail_id.clone(),
+                    server_id: tail.server_id.clone(),
+                    lines,
+                    dropped: tail.dropped,
+                })
+            } else {
+                None
+            };
+
+            let finished = (stop.load(Ordering::Relaxed) || tail.active_streams == 0) && tail.pending.is_empty();
+            if finished {
+                tail.finished_at = Some(Instant::now());
+            }
+            (batch, finished)
+        };
+
+        if let Some(batch) = batch {
+            let _ = app.emit("log_tail_lines", batch);
+        }
+        if finished {
+            let _ = app.emit("log_tail_end", serde_json::json!({ "tailId": tail_id }));
+            println!("⏹️ Tail {} terminato", tail_id);
+            prune_finished_tails();
+            break;
+        }
+    });
+}
+
+// ✅ COMANDO: Il frontend conferma di aver elaborato l'ultimo blocco
+#[command]
+pub fn ack_log_tail(tail_id: String) -> Result<(), String> {
+    get_tail(&tail_id)?.lock().unwrap().awaiting_ack = false;
+    Ok(())
+}
+
+// ✅ COMANDO: Ferma il tail; la finestra catturata resta disponibile per il salvataggio
+// (fino a `discard`, per CAPTURE_TTL e per gli ultimi MAX_KEPT_CAPTURES tail terminati)
+#[command]
+pub fn stop_log_tail(tail_id: String, discard: Option<bool>) -> Result<(), String> {
+    let state = get_tail(&tail_id)?;
+    {
+        let mut tail = state.lock().unwrap();
+        tail.stop.store(true, Ordering::Relaxed);
+        tail.pending.clear();
+        for stream_id in &tail.stream_ids {
+            // Lo stream potrebbe essere già terminato da solo
+            let _ = stop_remote_stream(stream_id.clone());
+        }
+    }
+
+    if discard.unwrap_or(false) {
+        tails().lock().unwrap().remove(&tail_id);
+    }
+    Ok(())
+}
+
+// ✅ COMANDO: Salva la finestra catturata su file locale (dialog se il percorso non è indicato)
+#[command]
+pub async fn save_log_capture(app: AppHandle, tail_id: String, path: Option<String>) -> Result<String, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let lines: Vec<LogLine> = get_tail(&tail_id)?.lock().unwrap().capture.iter().cloned().collect();
+    if lines.is_empty() {
+        return Err("Nessuna riga catturata da salvare".to_string());
+    }
+
+    let path = match path {
+        Some(path) => std::path::PathBuf::from(path),
+        None => {
+            let default_name = format!("devpulse-log-{}.log", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+            let file_path = app
+                .dialog()
+                .file()
+                .set_file_name(&default_name)
+                .add_filter("Log", &["log", "txt"])
+                .blocking_save_file()
+                .ok_or("Salvataggio annullato")?;
+            file_path.as_path().ok_or("Percorso file non valido")?.to_path_buf()
+        }
+    };
+
+    let content: String = lines
+        .iter()
+        .map(|l| format!("{} [{}] {}\n", l.received_at, l.source, l.line))
+        .collect();
+    fs::write(&path, content).map_err(|e| format!("Errore scrittura {}: {}", path.display(), e))?;
+
+    println!("💾 {} righe di log salvate in {}", lines.len(), path.display());
+    Ok(path.to_string_lossy().to_string())
+}
@@ -2,21 +2,101 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
-use tauri::{command, AppHandle, Manager};
+use tauri::{command, AppHandle, Manager, RunEvent};
 use tauri_plugin_fs;
 use terminal::{open_terminal, logout_terminal, check_terminal_status}; 
 use setup::{check_system_info, install_sshpass_for_devpulse}; // 🆕 Setup module
-use power_management::{wake_server, shutdown_server, test_network_connectivity};
+use power_management::{wake_server, shutdown_server, test_network_connectivity, bulk_power_action};
+use sftp::{
+    sftp_list_dir, sftp_stat, sftp_mkdir, sftp_rename, sftp_delete, sftp_chmod,
+    sftp_download, sftp_upload, sftp_cancel_transfer,
+};
+use remote_exec::{run_remote_command, stop_remote_stream};
+use docker::{
+    docker_list_containers, docker_inspect_container, docker_container_action,
+    docker_container_stats, docker_follow_logs,
+};
+use broadcast::broadcast_command;
+use inventory::{collect_server_inventory, get_server_inventory};
+use systemd::{
+    systemd_list_units, systemd_unit_action, systemd_follow_journal,
+    set_pinned_services, check_server_health,
+};
+use log_tail::{start_log_tail, ack_log_tail, stop_log_tail, save_log_capture};
+use updates::{check_package_updates, get_package_update_status, apply_package_updates};
+use snippets::{
+    list_snippets, save_snippet, delete_snippet, get_snippet_placeholders,
+    render_snippet, execute_snippet, inject_snippet_into_terminal,
+};
+use runbooks::{
+    list_runbooks, save_runbook, delete_runbook, run_runbook,
+    confirm_runbook_step, cancel_runbook,
+};
+use scheduler::{
+    start_scheduler, list_scheduled_jobs, save_scheduled_job, delete_scheduled_job,
+    set_scheduled_job_enabled, run_scheduled_job_now, get_scheduled_job_history, preview_cron,
+};
+use groups::{
+    resolve_selector, list_server_groups, save_server_group, delete_server_group,
+    assign_servers_to_group, unassign_servers_from_group, add_server_tags,
+    remove_server_tags, list_server_tags, query_servers,
+};
+use session_registry::{start_ssh_session, close_ssh_session, list_ssh_sessions, close_all_ssh_sessions};
+use ssh_config::{preview_ssh_config_import, import_ssh_config};
+use server_export::{render_server_export, export_servers};
+use server_import::{
+    apply_preview, build_preview, pick_import_file, MergeMode,
+    preview_servers_import, import_servers_with_mode,
+};
+use backup_archive::{export_backup_archive, inspect_backup_archive, restore_backup_archive};
+use mdns_discovery::{browse_mdns_services, advertise_mdns_service, stop_mdns_advertisement};
+use network_discovery::{discover_servers, cancel_discovery};
+use session_import::{preview_sessions_import, import_sessions};
+use snapshots::{
+    snapshot_before_change, start_snapshot_scheduler, list_restore_points, create_restore_point,
+    rollback_to_restore_point, get_snapshot_policy, set_snapshot_policy,
+};
+use validation::{validate_server, validate_server_record, parse_server_records};
+use metrics::{collect_server_metrics, get_metrics_history, start_metrics_collection, stop_metrics_collection};
+use tunnels::{save_port_forward, delete_port_forward, start_tunnel, stop_tunnel, list_tunnels, stop_all_tunnels, PortForward};
 
 mod terminal;
 mod setup;  // 🆕 Nuovo modulo setup
 mod power_management;
+mod ssh_session;
+mod tunnels;
+mod sftp;
+mod remote_exec;
+mod broadcast;
+mod metrics;
+mod inventory;
+mod docker;
+mod systemd;
+mod log_tail;
+mod updates;
+mod snippets;
+mod runbooks;
+mod scheduler;
+mod groups;
+mod session_registry;
+mod server_import;
+mod ssh_config;
+mod server_export;
+mod validation;
+mod backup_archive;
+mod snapshots;
+mod session_import;
+mod network_discovery;
+mod mdns_discovery;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Server {
     pub id: String,
@@ -34,6 +114,17 @@ pub struct Server {
     pub mac_address: Option<String>,
     pub wol_enabled: Option<bool>,
     pub shutdown_command: Option<String>,
+    // 🆕 Jump host / bastion: id di un altro server (la catena segue i suoi jump_host)
+    pub jump_host: Option<String>,
+    // 🆕 Port forwarding (-L / -R / -D) definiti per questo server
+    #[serde(default)]
+    pub port_forwards: Vec<PortForward>,
+    // 🆕 Servizi systemd inclusi nel controllo di salute
+    #[serde(default)]
+    pub pinned_services: Vec<String>,
+    // 🆕 Tag liberi (i gruppi annidati sono in groups.json)
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -97,8 +188,12 @@ async fn debug_system_state(app: AppHandle) -> Result<String, String> {
 }
 
 #[command]
-async fn ping_all_servers(app: AppHandle) -> Result<Vec<(String, PingResult)>, String> {
-    let servers = load_servers(app).await?;
+async fn ping_all_servers(app: AppHandle, selector: Option<String>) -> Result<Vec<(String, PingResult)>, String> {
+    // 🆕 Selettore opzionale di tag/gruppi (es. "group:prod")
+    let servers = match selector.as_deref().filter(|s| !s.trim().is_empty()) {
+        Some(selector) => resolve_selector(&app, selector).await?,
+        None => load_servers(app).await?,
+    };
     let mut results = Vec::new();
     for server in servers {
         let ping = ping_server(server.ip.clone(), server.ssh_port).await?;
@@ -109,6 +204,15 @@ async fn ping_all_servers(app: AppHandle) -> Result<Vec<(String, PingResult)>, S
 
 #[command]
 async fn save_server(app: AppHandle, server: Server) -> Result<(), String> {
+    // 🆕 Niente record semanticamente rotti in servers.json (ip vuoto, porta 0, MAC errato...)
+    let validation = validate_server(&server);
+    if !validation.is_valid() {
+        return Err(format!("Server non valido: {}", validation.summary()));
+    }
+    for warning in &validation.warnings {
+        println!("⚠️ {}: {} - {}", server.name, warning.field, warning.message);
+    }
+
     let path = app
         .path()
         .app_data_dir()
@@ -119,14 +223,19 @@ async fn save_server(app: AppHandle, server: Server) -> Result<(), String> {
         fs::create_dir_all(dir).map_err(|e| e.to_string())?;
     }
 
-    let mut list = if path.exists() {
+    let mut list: Vec<Server> = if path.exists() {
         let content = fs::read_to_string(&path).unwrap_or_default();
         serde_json::from_str(&content).unwrap_or_default()
     } else {
         vec![]
     };
 
-    list.push(server);
+    // 🆕 Upsert per id: la modifica sostituisce il record esistente invece di cancellarlo e riaggiungerlo
+    match list.iter_mut().find(|s| !server.id.is_empty() && s.id == server.id) {
+        Some(existing) => *existing = server,
+        None => list.push(server),
+    }
+    snapshot_before_change(&app, "salvataggio server")?;
     let json = serde_json::to_string_pretty(&list).map_err(|e| e.to_string())?;
     fs::write(path, json).map_err(|e| e.to_string())?;
     Ok(())
@@ -166,12 +275,66 @@ async fn delete_server(app: AppHandle, id: String) -> Result<(), String> {
     let mut servers: Vec<Server> = serde_json::from_str(&content).unwrap_or_default();
     
     servers.retain(|s| s.id != id);
+    snapshot_before_change(&app, "eliminazione server")?;
     
     let json = serde_json::to_string_pretty(&servers).map_err(|e| e.to_string())?;
     fs::write(path, json).map_err(|e| e.to_string())?;
     Ok(())
 }
 
+// 🆕 Scrive l'intera lista server (usato dai moduli che modificano i server salvati)
+fn store_servers(app: &AppHandle, servers: &[Server]) -> Result<(), String> {
+    let path = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Errore path: {e}"))?
+        .join("servers.json");
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+
+    snapshot_before_change(app, "modifica server")?;
+    let json = serde_json::to_string_pretty(servers).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// 🆕 Percorso di un file dati dell'app (accanto a servers.json)
+fn app_data_file(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Errore path: {e}"))?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join(name))
+}
+
+// 🆕 Lettura/scrittura JSON per gli store dei moduli (snippet, job, inventario...)
+fn read_json_file<T: DeserializeOwned + Default>(app: &AppHandle, name: &str) -> Result<T, String> {
+    let path = app_data_file(app, name)?;
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| format!("File {} non valido: {}", name, e))
+}
+
+fn write_json_file<T: Serialize>(app: &AppHandle, name: &str, value: &T) -> Result<(), String> {
+    let path = app_data_file(app, name)?;
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// 🆕 Id univoci per oggetti creati dal backend (trasferimenti, job, snippet...)
+fn new_id(prefix: &str) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{}-{}-{}", prefix, chrono::Utc::now().timestamp_millis(), count)
+}
+
 // 🆕 NUOVO: Funzioni per import/export (per il tuo BackupSettings)
 #[command]
 async fn export_servers_to_file(app: AppHandle) -> Result<String, String> {
@@ -198,45 +361,22 @@ async fn export_servers_to_file(app: AppHandle) -> Result<String, String> {
 }
 
 #[command]
-async fn import_servers_from_file(app: AppHandle) -> Result<u32, String> {
-    use tauri_plugin_dialog::DialogExt;
-    
-    // Apri dialog per selezionare file
-    let file_path = app
-        .dialog()
-        .file()
-        .add_filter("JSON files", &["json"])
-        .blocking_pick_file()
-        .ok_or("Nessun file selezionato")?;
-    
-    // Converti FilePath in Path gestendo l'Option
-    let file_path_buf = file_path.as_path().ok_or("Percorso file non valido")?;
-    
+async fn import_servers_from_file(app: AppHandle, mode: Option<String>) -> Result<u32, String> {
+    // 🆕 Merge invece della sostituzione completa (default "replace" per compatibilità)
+    let mode = MergeMode::parse(mode.as_deref(), MergeMode::Replace)?;
+    let file_path = pick_import_file(&app, None, "JSON files", &["json"])?;
+
     // Leggi il file selezionato
-    let content = fs::read_to_string(file_path_buf).map_err(|e| e.to_string())?;
-    let imported_servers: Vec<Server> = serde_json::from_str(&content)
-        .map_err(|e| format!("File JSON non valido: {}", e))?;
-    
-    // Backup del file esistente
-    let app_data_path = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Errore path: {e}"))?;
-    
-    let servers_path = app_data_path.join("servers.json");
-    
-    if servers_path.exists() {
-        let backup_name = format!("servers-backup-{}.json", 
-                                chrono::Local::now().format("%Y%m%d-%H%M%S"));
-        let backup_path = app_data_path.join(backup_name);
-        let _ = fs::copy(&servers_path, backup_path);
-    }
-    
-    // Salva i nuovi server come servers.json
-    let json = serde_json::to_string_pretty(&imported_servers).map_err(|e| e.to_string())?;
-    fs::write(servers_path, json).map_err(|e| e.to_string())?;
-    
-    Ok(imported_servers.len() as u32)
+    let content = fs::read_to_string(&file_path).map_err(|e| e.to_string())?;
+    // 🆕 I record non validi vengono scartati, gli altri importati
+    let (imported_servers, issues) = parse_server_records(&content)?;
+
+    // Backup del file esistente e merge secondo la modalità scelta
+    let existing = load_servers(app.clone()).await?;
+    let preview = build_preview(&file_path.to_string_lossy(), &existing, imported_servers, Vec::new(), issues, mode, true);
+    let applied = apply_preview(&app, existing, preview)?;
+
+    Ok((applied.created + applied.updated + applied.unchanged) as u32)
 }
 
 fn main() {
@@ -245,6 +385,13 @@ fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init()) // 🆕 Per i dialog di import/export
+        .setup(|app| {
+            // 🆕 Scheduler dei job pianificati
+            start_scheduler(app.handle().clone());
+            // 🆕 Restore point giornalieri e migrazione dei vecchi backup
+            start_snapshot_scheduler(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // ✅ Funzioni esistenti
             greet,
@@ -272,7 +419,155 @@ fn main() {
             wake_server,
             shutdown_server, 
             test_network_connectivity,
+
+            // 🆕 Tunnel / port forwarding
+            save_port_forward,
+            delete_port_forward,
+            start_tunnel,
+            stop_tunnel,
+            list_tunnels,
+
+            // 🆕 SFTP
+            sftp_list_dir,
+            sftp_stat,
+            sftp_mkdir,
+            sftp_rename,
+            sftp_delete,
+            sftp_chmod,
+            sftp_download,
+            sftp_upload,
+            sftp_cancel_transfer,
+
+            // 🆕 Esecuzione comandi remoti
+            run_remote_command,
+            broadcast_command,
+            stop_remote_stream,
+
+            // 🆕 Metriche di sistema remote
+            collect_server_metrics,
+            get_metrics_history,
+            start_metrics_collection,
+            stop_metrics_collection,
+
+            // 🆕 Inventario OS / hardware
+            collect_server_inventory,
+            get_server_inventory,
+
+            // 🆕 Docker
+            docker_list_containers,
+            docker_inspect_container,
+            docker_container_action,
+            docker_container_stats,
+            docker_follow_logs,
+
+            // 🆕 systemd
+            systemd_list_units,
+            systemd_unit_action,
+            systemd_follow_journal,
+            set_pinned_services,
+            check_server_health,
+
+            // 🆕 Log remoti
+            start_log_tail,
+            ack_log_tail,
+            stop_log_tail,
+            save_log_capture,
+
+            // 🆕 Aggiornamenti pacchetti
+            check_package_updates,
+            get_package_update_status,
+            apply_package_updates,
+
+            // 🆕 Snippet
+            list_snippets,
+            save_snippet,
+            delete_snippet,
+            get_snippet_placeholders,
+            render_snippet,
+            execute_snippet,
+            inject_snippet_into_terminal,
+
+            // 🆕 Runbook
+            list_runbooks,
+            save_runbook,
+            delete_runbook,
+            run_runbook,
+            confirm_runbook_step,
+            cancel_runbook,
+
+            // 🆕 Job pianificati
+            list_scheduled_jobs,
+            save_scheduled_job,
+            delete_scheduled_job,
+            set_scheduled_job_enabled,
+            run_scheduled_job_now,
+            get_scheduled_job_history,
+            preview_cron,
+
+            // 🆕 Tag e gruppi
+            list_server_groups,
+            save_server_group,
+            delete_server_group,
+            assign_servers_to_group,
+            unassign_servers_from_group,
+            add_server_tags,
+            remove_server_tags,
+            list_server_tags,
+            query_servers,
+            bulk_power_action,
+
+            // 🆕 Sessioni SSH (usate da ServerContext)
+            start_ssh_session,
+            close_ssh_session,
+            list_ssh_sessions,
+
+            // 🆕 Import da ~/.ssh/config
+            preview_ssh_config_import,
+            import_ssh_config,
+
+            // 🆕 Export ssh_config / Ansible / CSV
+            render_server_export,
+            export_servers,
+
+            // 🆕 Import con merge e dry-run
+            preview_servers_import,
+            import_servers_with_mode,
+
+            // 🆕 Validazione dei server
+            validate_server_record,
+
+            // 🆕 Backup cifrati
+            export_backup_archive,
+            inspect_backup_archive,
+            restore_backup_archive,
+
+            // 🆕 Restore point automatici
+            list_restore_points,
+            create_restore_point,
+            rollback_to_restore_point,
+            get_snapshot_policy,
+            set_snapshot_policy,
+
+            // 🆕 Import da PuTTY / Termius / Remmina / MobaXterm
+            preview_sessions_import,
+            import_sessions,
+
+            // 🆕 Discovery dei server in rete
+            discover_servers,
+            cancel_discovery,
+
+            // 🆕 Discovery mDNS / DNS-SD
+            browse_mdns_services,
+            advertise_mdns_service,
+            stop_mdns_advertisement,
         ])
-        .run(tauri::generate_context!())
-        .expect("Errore avvio DevPulse");
+        .build(tauri::generate_context!())
+        .expect("Errore avvio DevPulse")
+        .run(|_app, event| {
+            // 🆕 Chiusura pulita delle connessioni in background
+            if let RunEvent::Exit = event {
+                stop_all_tunnels();
+                close_all_ssh_sessions();
+            }
+        });
 }
\ No newline at end of file
@@ -0,0 +1,292 @@
+// src-tauri/src/mdns_discovery.rs
+// Discovery mDNS / DNS-SD degli host della rete locale (_ssh._tcp, _sftp-ssh._tcp, _workstation._tcp)
+// browse_mdns_services ascolta gli annunci per un periodo configurabile e raggruppa i servizi per host
+// advertise_mdns_service pubblica un servizio dallo stesso processo (prova locale senza altri host)
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Emitter};
+
+use crate::load_servers;
+
+const DEFAULT_SERVICE_TYPES: &[&str] = &["_ssh._tcp", "_sftp-ssh._tcp", "_workstation._tcp"];
+const DEFAULT_BROWSE_MS: u64 = 3000;
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// Un solo daemon per processo: browse e servizi pubblicati condividono il socket multicast
+static MDNS_DAEMON: OnceCell<ServiceDaemon> = OnceCell::new();
+static ADVERTISED: Lazy<Mutex<Vec<String>>> = Lazy::new(|| Mutex::new(Vec::new()));
+// Browse serializzati: sullo stesso daemon lo stop_browse di un browse chiuderebbe quello in corso dell'altro
+static BROWSE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MdnsBrowseRequest {
+    pub service_types: Option<Vec<String>>, // default: ssh, sftp-ssh, workstation
+    pub duration_ms: Option<u64>,
+    pub include_known: Option<bool>,        // default: esclude gli host già in inventario
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MdnsService {
+    pub service_type: String,
+    pub instance: String,
+    pub port: u16,
+    pub txt: HashMap<String, String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MdnsHost {
+    pub hostname: String,                // es. "nas.local."
+    pub name: String,                    // nome suggerito per il server
+    pub addresses: Vec<String>,
+    pub ssh_port: Option<u16>,           // da _ssh._tcp o _sftp-ssh._tcp
+    pub services: Vec<MdnsService>,
+    pub known_server_id: Option<String>,
+}
+
+fn daemon() -> Result<&'static ServiceDaemon, String> {
+    MDNS_DAEMON.get_or_try_init(|| ServiceDaemon::new().map_err(|e| format!("Avvio mDNS fallito: {}", e)))
+}
+
+// "_ssh._tcp" -> "_ssh._tcp.local."
+fn full_service_type(service_type: &str) -> Result<String, String> {
+    let base = service_type.trim().trim_end_matches('.').trim_end_matches(".local");
+    let valid = base.starts_with('_') && (base.ends_with("._tcp") || base.ends_with("._udp"));
+    if !valid {
+        return Err(format!("Tipo di servizio non valido: {} (es. _ssh._tcp)", service_type));
+    }
+    Ok(format!("{}.local.", base))
+}
+
+// "Studio NAS._ssh._tcp.local." -> "Studio NAS"
+fn instance_name(fullname: &str, service_type: &str) -> String {
+    fullname
+        .strip_suffix(service_type)
+        .map(|n| n.trim_end_matches('.'))
+        .unwrap_or(fullname)
+        .to_string()
+}
+
+fn add_resolved(hosts: &mut Vec<MdnsHost>, service_type: &str, info: &ServiceInfo) {
+    let hostname = info.get_hostname().to_string();
+    let mut addresses: Vec<String> = info.get_addresses().iter().map(|a| a.to_string()).collect();
+    addresses.sort();
+
+    let service = MdnsService {
+        service_type: service_type.trim_end_matches('.').trim_end_matches(".local").to_string(),
+        instance: instance_name(info.get_fullname(), service_type),
+        port: info.get_port(),
+        txt: info.get_properties().iter().map(|p| (p.key().to_string(), p.val_str().to_string())).collect(),
+    };
+    let is_ssh = service.service_type == "_ssh._tcp" || service.service_type == "_sftp-ssh._tcp";
+
+    let index = match hosts.iter().position(|h| h.hostname.eq_ignore_ascii_case(&hostname)) {
+        Some(index) => index,
+        None => {
+            hosts.push(MdnsHost {
+                name: hostname.trim_end_matches('.').trim_end_matches(".local").to_string(),
+                hostname,
+                addresses: Vec::new(),
+                ssh_port: None,
+                services: Vec::new(),
+                known_server_id: None,
+            });
+            hosts.len() - 1
+        }
+    };
+    let host = &mut hosts[index];
+
+    for address in addresses {
+        if !host.addresses.contains(&address) {
+            host.addresses.push(address);
+        }
+    }
+    if is_ssh {
+        // _ssh._tcp ha la precedenza su _sftp-ssh._tcp; il nome dell'istanza è più leggibile dell'hostname
+        if host.ssh_port.is_none() || service.service_type == "_ssh._tcp" {
+            host.ssh_port = Some(service.port);
+        }
+        host.name = service.instance.clone();
+    }
+    host.services.retain(|s| !(s.service_type == service.service_type && s.instance == service.instance));
+    host.services.push(service);
+}
+
+// Ascolta gli annunci fino alla scadenza (bloccante: eseguita con spawn_blocking)
+fn browse(app: &AppHandle, service_types: &[String], duration: Duration) -> Result<Vec<MdnsHost>, String> {
+    browse_with(service_types, duration, |service_type, info| {
+        let _ = app.emit("mdns_service_resolved", serde_json::json!({
+            "hostname": info.get_hostname(),
+            "serviceType": service_type,
+            "port": info.get_port(),
+        }));
+    })
+}
+
+// Il browse vero e proprio, con una callback per ogni servizio risolto (evento al frontend)
+fn browse_with(
+    service_types: &[String],
+    duration: Duration,
+    mut on_resolved: impl FnMut(&str, &ServiceInfo),
+) -> Result<Vec<MdnsHost>, String> {
+    let _browsing = BROWSE_LOCK.lock().unwrap();
+    let mdns = daemon()?;
+    let mut receivers = Vec::new();
+    for service_type in service_types {
+        let receiver = mdns
+            .browse(service_type)
+            .map_err(|e| format!("Browse {} fallito: {}", service_type, e))?;
+        receivers.push((service_type.clone(), receiver));
+    }
+
+    let mut hosts: Vec<MdnsHost> = Vec::new();
+    let deadline = Instant::now() + duration;
+    while Instant::now() < deadline {
+        let mut received = false;
+        for (service_type, receiver) in &receivers {
+            while let Ok(event) = receiver.try_recv() {
+                received = true;
+                if let ServiceEvent::ServiceResolved(info) = event {
+                    add_resolved(&mut hosts, service_type, &info);
+                    on_resolved(service_type.as_str(), &info);
+                }
+            }
+        }
+        if !received {
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    for service_type in service_types {
+        let _ = mdns.stop_browse(service_type);
+    }
+    Ok(hosts)
+}
+
+// ✅ COMANDO: Cerca host e servizi annunciati via mDNS per il periodo indicato
+#[command]
+pub async fn browse_mdns_services(app: AppHandle, request: Option<MdnsBrowseRequest>) -> Result<Vec<MdnsHost>, String> {
+    let request = request.unwrap_or_default();
+    let service_types = match request.service_types.filter(|t| !t.is_empty()) {
+        Some(types) => types.iter().map(|t| full_service_type(t)).collect::<Result<Vec<_>, _>>()?,
+        None => DEFAULT_SERVICE_TYPES.iter().map(|t| full_service_type(t)).collect::<Result<Vec<_>, _>>()?,
+    };
+    let duration = Duration::from_millis(request.duration_ms.unwrap_or(DEFAULT_BROWSE_MS).clamp(500, 60_000));
+
+    println!("📡 Browse mDNS {:?} per {} ms", service_types, duration.as_millis());
+    let browse_app = app.clone();
+    let mut hosts = tokio::task::spawn_blocking(move || browse(&browse_app, &service_types, duration))
+        .await
+        .map_err(|e| format!("Errore task mDNS: {}", e))??;
+
+    let existing = load_servers(app).await?;
+    let include_known = request.include_known.unwrap_or(false);
+    hosts.retain_mut(|host| {
+        let short = host.hostname.trim_end_matches('.').to_lowercase();
+        host.known_server_id = existing
+            .iter()
+            .find(|s| {
+                let ip = s.ip.trim_end_matches('.').to_lowercase();
+                host.addresses.contains(&s.ip) || ip == short || ip == short.trim_end_matches(".local")
+            })
+            .map(|s| s.id.clone());
+        include_known || host.known_server_id.is_none()
+    });
+    hosts.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+
+    println!("📡 mDNS: {} host trovati", hosts.len());
+    Ok(hosts)
+}
+
+// ✅ COMANDO: Pubblica un servizio mDNS da DevPulse (es. per provare il browse in locale)
+#[command]
+pub fn advertise_mdns_service(
+    service_type: String,
+    instance: String,
+    port: u16,
+    txt: Option<HashMap<String, String>>,
+) -> Result<String, String> {
+    let service_type = full_service_type(&service_type)?;
+    if instance.trim().is_empty() || port == 0 {
+        return Err("Nome istanza e porta sono obbligatori".to_string());
+    }
+
+    let host = std::env::var("HOSTNAME")
+        .ok()
+        .filter(|h| !h.trim().is_empty())
+        .unwrap_or_else(|| "devpulse".to_string());
+    let hostname = format!("{}.local.", host.trim_end_matches(".local"));
+    let info = ServiceInfo::new(&service_type, instance.trim(), &hostname, "", port, txt)
+        .map_err(|e| format!("Servizio non valido: {}", e))?
+        .enable_addr_auto();
+    let fullname = info.get_fullname().to_string();
+
+    daemon()?.register(info).map_err(|e| format!("Pubblicazione mDNS fallita: {}", e))?;
+    ADVERTISED.lock().unwrap().push(fullname.clone());
+    println!("📣 Servizio mDNS pubblicato: {}", fullname);
+    Ok(fullname)
+}
+
+// ✅ COMANDO: Ritira un servizio pubblicato (o tutti, senza fullname)
+#[command]
+pub fn stop_mdns_advertisement(fullname: Option<String>) -> Result<usize, String> {
+    let mdns = daemon()?;
+    let mut advertised = ADVERTISED.lock().unwrap();
+    let targets: Vec<String> = match fullname {
+        Some(name) if advertised.contains(&name) => vec![name],
+        Some(name) => return Err(format!("Servizio '{}' non pubblicato da DevPulse", name)),
+        None => advertised.clone(),
+    };
+
+    for name in &targets {
+        let _ = mdns.unregister(name);
+    }
+    advertised.retain(|n| !targets.contains(n));
+    Ok(targets.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_types_and_instance_names() {
+        assert_eq!(full_service_type("_ssh._tcp").unwrap(), "_ssh._tcp.local.");
+        assert_eq!(full_service_type("_ssh._tcp.local.").unwrap(), "_ssh._tcp.local.");
+        assert!(full_service_type("ssh").is_err());
+        assert_eq!(instance_name("Studio NAS._ssh._tcp.local.", "_ssh._tcp.local."), "Studio NAS");
+    }
+
+    // Pubblica _ssh._tcp sul daemon condiviso e lo ritrova con il browse dello stesso processo
+    #[test]
+    fn advertised_ssh_service_round_trip() {
+        let instance = format!("DevPulse Test {}", std::process::id());
+        let txt = HashMap::from([("path".to_string(), "/srv".to_string())]);
+        let fullname = advertise_mdns_service("_ssh._tcp".to_string(), instance.clone(), 2222, Some(txt)).unwrap();
+
+        let mut resolved = 0;
+        let hosts = browse_with(&["_ssh._tcp.local.".to_string()], Duration::from_secs(3), |_, _| resolved += 1);
+        stop_mdns_advertisement(Some(fullname)).unwrap();
+        let hosts = hosts.unwrap();
+
+        let host = hosts
+            .iter()
+            .find(|h| h.services.iter().any(|s| s.instance == instance))
+            .expect("servizio pubblicato non trovato dal browse");
+        assert!(resolved > 0);
+        assert_eq!(host.name, instance);
+        assert_eq!(host.ssh_port, Some(2222));
+        assert!(host.hostname.ends_with(".local."));
+
+        let service = host.services.iter().find(|s| s.instance == instance).unwrap();
+        assert_eq!(service.service_type, "_ssh._tcp");
+        assert_eq!(service.txt.get("path").map(String::as_str), Some("/srv"));
+    }
+}
@@ -0,0 +1,388 @@
+// src-tauri/src/metrics.rs
+// Metriche di sistema remote (CPU, memoria, dischi, load, uptime) via SSH
+// Linux legge /proc, macOS/BSD usa sysctl, vm_stat e top (macOS) o kern.cp_time (FreeBSD)
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use tauri::{command, AppHandle, Emitter};
+
+use crate::remote_exec::{execute_on_server, shell_quote, split_output_sections, ExecOptions};
+use crate::ssh_session::find_server;
+use crate::{load_servers, Server};
+
+static METRICS_HISTORY: OnceCell<Mutex<HashMap<String, VecDeque<ServerMetrics>>>> = OnceCell::new();
+static COLLECTOR_STOP: OnceCell<Mutex<Option<Arc<AtomicBool>>>> = OnceCell::new();
+
+const HISTORY_CAPACITY: usize = 120;
+const MIN_INTERVAL_SECS: u64 = 10;
+
+// Un solo round-trip SSH: sezioni separate da marcatori ==NOME==
+// Sintassi sh: va eseguito con `sh -c`, la shell di login può essere csh (FreeBSD) o fish
+const METRICS_SCRIPT: &str = r#"echo ==OS==; uname -s
+if [ -r /proc/stat ]; then
+  echo ==STAT1==; head -n 1 /proc/stat; sleep 1
+  echo ==STAT2==; head -n 1 /proc/stat
+  echo ==MEMINFO==; cat /proc/meminfo
+  echo ==LOADAVG==; cat /proc/loadavg
+  echo ==UPTIME==; cat /proc/uptime
+else
+  echo ==MEMSIZE==; sysctl -n hw.memsize 2>/dev/null || sysctl -n hw.physmem
+  echo ==VMSTAT==; vm_stat 2>/dev/null
+  echo ==BSDLOAD==; sysctl -n vm.loadavg
+  echo ==BOOTTIME==; sysctl -n kern.boottime
+  if [ "$(uname -s)" = Darwin ]; then
+    echo ==TOP==; top -l 1 -n 0 2>/dev/null | grep -i 'cpu usage'
+  else
+    echo ==CPTIME1==; sysctl -n kern.cp_time; sleep 1
+    echo ==CPTIME2==; sysctl -n kern.cp_time
+  fi
+fi
+echo ==DF==; df -Pk 2>/dev/null"#;
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsage {
+    pub filesystem: String,
+    pub mount_point: String,
+    pub total_kb: u64,
+    pub used_kb: u64,
+    pub available_kb: u64,
+    pub used_percent: f64,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerMetrics {
+    pub server_id: String,
+    pub collected_at: String,
+    pub os: String,
+    pub cpu_usage_percent: Option<f64>,
+    pub load_average: Option<[f64; 3]>,
+    pub memory_total_kb: Option<u64>,
+    pub memory_available_kb: Option<u64>,
+    pub memory_used_percent: Option<f64>,
+    pub swap_total_kb: Option<u64>,
+    pub swap_used_kb: Option<u64>,
+    pub uptime_secs: Option<u64>,
+    pub disks: Vec<DiskUsage>,
+}
+
+// ✅ COMANDO: Raccoglie subito le metriche di un server
+#[command]
+pub async fn collect_server_metrics(app: AppHandle, server_id: String) -> Result<ServerMetrics, String> {
+    let (server, servers) = find_server(&app, &server_id).await?;
+    collect_and_store(&app, server, servers).await
+}
+
+// ✅ COMANDO: Storico metriche (ring buffer) per i grafici sparkline
+#[command]
+pub fn get_metrics_history(server_id: String, limit: Option<usize>) -> Vec<ServerMetrics> {
+    let Some(lock) = METRICS_HISTORY.get() else {
+        return Vec::new();
+    };
+    let history = lock.lock().unwrap();
+    let Some(samples) = history.get(&server_id) else {
+        return Vec::new();
+    };
+
+    let limit = limit.unwrap_or(HISTORY_CAPACITY).min(samples.len());
+    samples.iter().skip(samples.len() - limit).cloned().collect()
+}
+
+// ✅ COMANDO: Avvia la raccolta periodica (insieme ai controlli di stato)
+#[command]
+pub async fn start_metrics_collection(
+    app: AppHandle,
+    interval_secs: Option<u64>,
+    server_ids: Option<Vec<String>>,
+) -> Result<(), String> {
+    let interval = Duration::from_secs(interval_secs.unwrap_or(60).max(MIN_INTERVAL_SECS));
+
+    let stop = Arc::new(AtomicBool::new(false));
+    {
+        let mut current = COLLECTOR_STOP.get_or_init(|| Mutex::new(None)).lock().unwrap();
+        if let Some(previous) = current.replace(stop.clone()) {
+            previous.store(true, Ordering::Relaxed);
+        }
+    }
+
+    println!("📈 Raccolta metriche ogni {}s", interval.as_secs());
+
+    tokio::spawn(async move {
+        while !stop.load(Ordering::Relaxed) {
+            if let Ok(servers) = load_servers(app.clone()).await {
+                let targets: Vec<Server> = servers
+                    .iter()
+                    .filter(|s| server_ids.as_ref().map_or(true, |ids| ids.contains(&s.id)))
+                    .cloned()
+                    .collect();
+
+                let tasks: Vec<_> = targets
+                    .into_iter()
+                    .map(|server| {
+                        let app = app.clone();
+                        let servers = servers.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = collect_and_store(&app, server.clone(), servers).await {
+                                println!("⚠️ Metriche {}: {}", server.name, e);
+                            }
+                        })
+                    })
+                    .collect();
+
+                for task in tasks {
+                    let _ = task.await;
+                }
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+        println!("📉 Raccolta metriche fermata");
+    });
+
+    Ok(())
+}
+
+// ✅ COMANDO: Ferma la raccolta periodica
+#[command]
+pub fn stop_metrics_collection() {
+    if let Some(lock) = COLLECTOR_STOP.get() {
+        if let Some(stop) = lock.lock().unwrap().take() {
+            stop.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+async fn collect_and_store(app: &AppHandle, server: Server, servers: Vec<Server>) -> Result<ServerMetrics, String> {
+    let options = ExecOptions {
+        timeout_secs: Some(30),
+        ..Default::default()
+    };
+    let server_id = server.id.clone();
+    let command = format!("sh -c {}", shell_quote(METRICS_SCRIPT));
+    let output = execute_on_server(app, server, servers, command, options).await?;
+
+    if output.timed_out {
+        return Err("Timeout raccolta metriche".to_string());
+    }
+
+    let mut metrics = parse_metrics_output(&output.stdout, chrono::Utc::now().timestamp());
+    metrics.server_id = server_id.clone();
+    metrics.collected_at = chrono::Local::now().to_rfc3339();
+
+    {
+        let mut history = METRICS_HISTORY.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap();
+        let samples = history.entry(server_id).or_default();
+        if samples.len() >= HISTORY_CAPACITY {
+            samples.pop_front();
+        }
+        samples.push_back(metrics.clone());
+    }
+
+    let _ = app.emit("server_metrics", metrics.clone());
+    Ok(metrics)
+}
+
+// ✅ Parsing dell'output dello script (funzione pura, `now` in epoch secondi)
+pub fn parse_metrics_output(output: &str, now: i64) -> ServerMetrics {
+    let sections = split_output_sections(output);
+    let section = |name: &str| sections.get(name).map(|v| v.as_slice()).unwrap_or(&[]);
+    let first = |name: &str| section(name).first().map(|s| s.as_str()).unwrap_or("");
+
+    let mut metrics = ServerMetrics {
+        os: first("OS").to_lowercase(),
+        disks: parse_df(section("DF")),
+        ..Default::default()
+    };
+
+    if sections.contains_key("STAT1") {
+        // Linux
+        metrics.cpu_usage_percent = cpu_percent(parse_proc_stat(first("STAT1")), parse_proc_stat(first("STAT2")));
+
+        let meminfo = parse_meminfo(section("MEMINFO"));
+        let total = meminfo.get("MemTotal").copied();
+        let available = meminfo.get("MemAvailable").copied().or_else(|| {
+            let free = meminfo.get("MemFree")?;
+            Some(free + meminfo.get("Buffers").unwrap_or(&0) + meminfo.get("Cached").unwrap_or(&0))
+        });
+        metrics.memory_total_kb = total;
+        metrics.memory_available_kb = available;
+        metrics.swap_total_kb = meminfo.get("SwapTotal").copied();
+        metrics.swap_used_kb = match (meminfo.get("SwapTotal"), meminfo.get("SwapFree")) {
+            (Some(total), Some(free)) => Some(total.saturating_sub(*free)),
+            _ => None,
+        };
+
+        metrics.load_average = parse_load(first("LOADAVG"));
+        metrics.uptime_secs = first("UPTIME")
+            .split_whitespace()
+            .next()
+            .and_then(|v| v.parse::<f64>().ok())
+            .map(|v| v as u64);
+    } else {
+        // macOS / BSD
+        metrics.memory_total_kb = first("MEMSIZE").parse::<u64>().ok().map(|bytes| bytes / 1024);
+        metrics.memory_available_kb = parse_vm_stat(section("VMSTAT"));
+        metrics.load_average = parse_load(first("BSDLOAD").trim_matches(|c| c == '{' || c == '}'));
+        metrics.uptime_secs = parse_boottime(first("BOOTTIME")).map(|boot| (now - boot).max(0) as u64);
+        metrics.cpu_usage_percent = if sections.contains_key("CPTIME1") {
+            cpu_percent(parse_cp_time(first("CPTIME1")), parse_cp_time(first("CPTIME2")))
+        } else {
+            parse_top_cpu(first("TOP"))
+        };
+    }
+
+    metrics.memory_used_percent = match (metrics.memory_total_kb, metrics.memory_available_kb) {
+        (Some(total), Some(available)) if total > 0 => {
+            Some(round2(total.saturating_sub(available) as f64 * 100.0 / total as f64))
+        }
+        _ => None,
+    };
+
+    metrics
+}
+
+// "cpu  user nice system idle iowait irq softirq steal ..." -> (idle, totale)
+fn parse_proc_stat(line: &str) -> Option<(u64, u64)> {
+    let mut fields = line.split_whitespace();
+    if fields.next()? != "cpu" {
+        return None;
+    }
+
+    let values: Vec<u64> = fields.filter_map(|v| v.parse().ok()).collect();
+    if values.len() < 4 {
+        return None;
+    }
+
+    // idle + iowait contano come tempo inattivo; guest è già incluso in user
+    let idle = values[3] + values.get(4).copied().unwrap_or(0);
+    let total = values.iter().take(8).sum();
+    Some((idle, total))
+}
+
+// kern.cp_time (FreeBSD): "user nice sys intr idle" -> (idle, totale)
+fn parse_cp_time(line: &str) -> Option<(u64, u64)> {
+    let values: Vec<u64> = line.split_whitespace().filter_map(|v| v.parse().ok()).collect();
+    (values.len() == 5).then(|| (values[4], values.iter().sum()))
+}
+
+// Percentuale di CPU occupata tra due campioni (idle, totale)
+fn cpu_percent(first: Option<(u64, u64)>, second: Option<(u64, u64)>) -> Option<f64> {
+    match (first, second) {
+        (Some((idle1, total1)), Some((idle2, total2))) if total2 > total1 => {
+            let busy = (total2 - total1).saturating_sub(idle2.saturating_sub(idle1));
+            Some(round2(busy as f64 * 100.0 / (total2 - total1) as f64))
+        }
+        _ => None,
+    }
+}
+
+fn parse_meminfo(lines: &[String]) -> HashMap<String, u64> {
+    lines
+        .iter()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let value = rest.split_whitespace().next()?.parse().ok()?;
+            Some((key.trim().to_string(), value))
+        })
+        .collect()
+}
+
+fn parse_load(line: &str) -> Option<[f64; 3]> {
+    let values: Vec<f64> = line
+        .split_whitespace()
+        .take(3)
+        .filter_map(|v| v.parse().ok())
+        .collect();
+    (values.len() == 3).then(|| [values[0], values[1], values[2]])
+}
+
+// vm_stat: (free + inactive + speculative) pagine -> KB
+fn parse_vm_stat(lines: &[String]) -> Option<u64> {
+    let page_size = lines
+        .first()
+        .and_then(|l| l.split("page size of ").nth(1))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(4096);
+
+    let pages = |name: &str| -> u64 {
+        lines
+            .iter()
+            .find(|l| l.starts_with(name))
+            .and_then(|l| l.split(':').nth(1))
+            .and_then(|v| v.trim().trim_end_matches('.').parse().ok())
+            .unwrap_or(0)
+    };
+
+    let free_pages = pages("Pages free") + pages("Pages inactive") + pages("Pages speculative");
+    (!lines.is_empty()).then(|| free_pages * page_size / 1024)
+}
+
+// "{ sec = 1690000000, usec = 0 } Sat Jul 22 ..." -> 1690000000
+fn parse_boottime(line: &str) -> Option<i64> {
+    line.split("sec =")
+        .nth(1)?
+        .split(',')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+// "CPU usage: 5.12% user, 10.3% sys, 84.55% idle" -> 15.45
+fn parse_top_cpu(line: &str) -> Option<f64> {
+    let idle = line
+        .split(',')
+        .find(|part| part.contains("idle"))?
+        .split('%')
+        .next()?
+        .trim()
+        .parse::<f64>()
+        .ok()?;
+    Some(round2(100.0 - idle))
+}
+
+fn parse_df(lines: &[String]) -> Vec<DiskUsage> {
+    const PSEUDO_FS: [&str; 8] = ["tmpfs", "devtmpfs", "udev", "overlay", "shm", "devfs", "map", "none"];
+
+    lines
+        .iter()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 6 {
+                return None;
+            }
+
+            let filesystem = fields[0];
+            if PSEUDO_FS.contains(&filesystem) {
+                return None;
+            }
+
+            let total_kb: u64 = fields[1].parse().ok()?;
+            if total_kb == 0 {
+                return None;
+            }
+            let used_kb: u64 = fields[2].parse().ok()?;
+            let available_kb: u64 = fields[3].parse().ok()?;
+
+            Some(DiskUsage {
+                filesystem: filesystem.to_string(),
+                mount_point: fields[5..].join(" "),
+                total_kb,
+                used_kb,
+                available_kb,
+                used_percent: round2(used_kb as f64 * 100.0 / (used_kb + available_kb).max(1) as f64),
+            })
+        })
+        .collect()
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
@@ -0,0 +1,295 @@
+// src-tauri/src/network_discovery.rs
+// Scansione di una rete (CIDR IPv4) per trovare server SSH non ancora in inventario
+// Connessioni TCP in parallelo, banner SSH, DNS inverso e MAC dalla tabella ARP (per il WoL)
+// Avanzamento con eventi "discovery_progress" / "discovery_candidate", annullabile con cancel_discovery
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Emitter};
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpStream;
+use tokio::sync::Semaphore;
+
+use crate::{load_servers, new_id};
+
+const DEFAULT_PORTS: &[u16] = &[22];
+const DEFAULT_CONNECT_TIMEOUT_MS: u64 = 800;
+const DEFAULT_CONCURRENCY: usize = 128;
+const BANNER_TIMEOUT: Duration = Duration::from_millis(1000);
+const DNS_TIMEOUT: Duration = Duration::from_secs(2);
+const MIN_PREFIX: u8 = 20;               // massimo 4096 indirizzi per scansione
+const PROGRESS_EVERY: usize = 16;
+
+static DISCOVERY_SCANS: Lazy<Mutex<HashMap<String, Arc<AtomicBool>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveryRequest {
+    pub cidr: String,                    // "192.168.1.0/24" o un singolo indirizzo
+    pub ports: Option<Vec<u16>>,         // default: solo 22
+    pub connect_timeout_ms: Option<u64>,
+    pub concurrency: Option<usize>,
+    pub include_known: Option<bool>,     // default: esclude gli host già in inventario
+    pub scan_id: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenPort {
+    pub port: u16,
+    pub banner: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveryCandidate {
+    pub ip: String,
+    pub hostname: Option<String>,
+    pub mac_address: Option<String>,
+    pub open_ports: Vec<OpenPort>,
+    pub ssh_port: Option<u16>,           // prima porta che risponde con un banner "SSH-"
+    pub ssh_banner: Option<String>,
+    pub response_time_ms: u64,
+    pub known_server_id: Option<String>, // valorizzato solo con includeKnown
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveryResult {
+    pub scan_id: String,
+    pub cidr: String,
+    pub total: usize,
+    pub scanned: usize,
+    pub cancelled: bool,
+    pub duration_ms: u64,
+    pub candidates: Vec<DiscoveryCandidate>,
+}
+
+// "10.0.0.0/24" -> host della rete (senza indirizzo di rete e broadcast fino a /30)
+fn parse_cidr(input: &str) -> Result<Vec<Ipv4Addr>, String> {
+    let input = input.trim();
+    let (address, prefix) = input.split_once('/').unwrap_or((input, "32"));
+    let address: Ipv4Addr = address.trim().parse().map_err(|_| format!("Indirizzo IPv4 non valido: {}", address))?;
+    let prefix: u8 = prefix.trim().parse().ok().filter(|p| *p <= 32).ok_or_else(|| format!("Prefisso non valido: /{}", prefix))?;
+    if prefix < MIN_PREFIX {
+        return Err(format!("Rete troppo grande: /{} (massimo /{})", prefix, MIN_PREFIX));
+    }
+
+    let mask = u32::MAX << (32 - prefix);
+    let network = u32::from(address) & mask;
+    let broadcast = network | !mask;
+
+    let (first, last) = if prefix <= 30 { (network + 1, broadcast - 1) } else { (network, broadcast) };
+    Ok((first..=last).map(Ipv4Addr::from).collect())
+}
+
+// Prima riga inviata dal servizio appena connessi (SSH la invia sempre per primo)
+async fn read_banner(stream: &mut TcpStream) -> Option<String> {
+    let mut buffer = [0u8; 256];
+    let read = tokio::time::timeout(BANNER_TIMEOUT, stream.read(&mut buffer)).await.ok()?.ok()?;
+    let text = String::from_utf8_lossy(&buffer[..read]);
+    text.lines().next().map(|l| l.trim().to_string()).filter(|l| !l.is_empty())
+}
+
+async fn probe_port(ip: Ipv4Addr, port: u16, connect_timeout: Duration) -> Option<(u64, OpenPort)> {
+    let start = Instant::now();
+    let mut stream = tokio::time::timeout(connect_timeout, TcpStream::connect((ip, port))).await.ok()?.ok()?;
+    let elapsed = start.elapsed().as_millis() as u64;
+    let banner = read_banner(&mut stream).await;
+    Some((elapsed, OpenPort { port, banner }))
+}
+
+async fn reverse_dns(ip: Ipv4Addr) -> Option<String> {
+    let lookup = tokio::task::spawn_blocking(move || dns_lookup::lookup_addr(&IpAddr::V4(ip)).ok());
+    let hostname = tokio::time::timeout(DNS_TIMEOUT, lookup).await.ok()?.ok()??;
+    // Senza record PTR alcuni resolver ritornano l'indirizzo stesso
+    (hostname != ip.to_string()).then_some(hostname)
+}
+
+async fn probe_host(ip: Ipv4Addr, ports: &[u16], connect_timeout: Duration) -> Option<DiscoveryCandidate> {
+    let mut open_ports = Vec::new();
+    let mut response_time_ms = u64::MAX;
+    for port in ports {
+        if let Some((elapsed, open)) = probe_port(ip, *port, connect_timeout).await {
+            response_time_ms = response_time_ms.min(elapsed);
+            open_ports.push(open);
+        }
+    }
+    if open_ports.is_empty() {
+        return None;
+    }
+
+    let ssh = open_ports.iter().find(|p| p.banner.as_deref().map_or(false, |b| b.starts_with("SSH-")));
+    Some(DiscoveryCandidate {
+        ip: ip.to_string(),
+        hostname: reverse_dns(ip).await,
+        mac_address: None,
+        ssh_port: ssh.map(|p| p.port),
+        ssh_banner: ssh.and_then(|p| p.banner.clone()),
+        open_ports,
+        response_time_ms,
+        known_server_id: None,
+    })
+}
+
+fn normalize_mac(mac: &str) -> Option<String> {
+    let mac = mac.trim().to_uppercase().replace('-', ":");
+    let parts: Vec<&str> = mac.split(':').collect();
+    if parts.len() != 6 || mac == "00:00:00:00:00:00" {
+        return None;
+    }
+    // "arp -a" su macOS omette gli zeri iniziali (0:1c:42:...)
+    Some(parts.iter().map(|p| format!("{:0>2}", p)).collect::<Vec<_>>().join(":"))
+}
+
+// Tabella ARP / neighbor: /proc/net/arp su Linux, "arp -an" altrove
+async fn read_arp_table() -> HashMap<String, String> {
+    let mut table = HashMap::new();
+
+    if let Ok(content) = tokio::fs::read_to_string("/proc/net/arp").await {
+        // IP address  HW type  Flags  HW address  Mask  Device
+        for fields in content.lines().skip(1).map(|l| l.split_whitespace().collect::<Vec<_>>()) {
+            if let (Some(ip), Some(flags), Some(mac)) = (fields.first(), fields.get(2), fields.get(3)) {
+                if *flags != "0x0" {
+                    if let Some(mac) = normalize_mac(mac) {
+                        table.insert(ip.to_string(), mac);
+                    }
+                }
+            }
+        }
+        return table;
+    }
+
+    // ? (192.168.1.10) at aa:bb:cc:dd:ee:ff on en0 ...
+    if let Ok(output) = tokio::process::Command::new("arp").arg("-an").output().await {
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let ip = line.split('(').nth(1).and_then(|r| r.split(')').next());
+            let mac = line.split(" at ").nth(1).and_then(|r| r.split_whitespace().next());
+            if let (Some(ip), Some(mac)) = (ip, mac.and_then(normalize_mac)) {
+                table.insert(ip.to_string(), mac);
+            }
+        }
+    }
+    table
+}
+
+// ✅ COMANDO: Scansiona una rete e ritorna gli host raggiungibili non ancora salvati
+#[command]
+pub async fn discover_servers(app: AppHandle, request: DiscoveryRequest) -> Result<DiscoveryResult, String> {
+    let hosts = parse_cidr(&request.cidr)?;
+    let ports: Vec<u16> = request
+        .ports
+        .clone()
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| DEFAULT_PORTS.to_vec());
+    if ports.contains(&0) {
+        return Err("Porta 0 non valida".to_string());
+    }
+    let connect_timeout = Duration::from_millis(request.connect_timeout_ms.unwrap_or(DEFAULT_CONNECT_TIMEOUT_MS).clamp(100, 10_000));
+    let concurrency = request.concurrency.unwrap_or(DEFAULT_CONCURRENCY).clamp(1, 512);
+
+    let scan_id = request.scan_id.clone().unwrap_or_else(|| new_id("discovery"));
+    let cancel = Arc::new(AtomicBool::new(false));
+    DISCOVERY_SCANS.lock().unwrap().insert(scan_id.clone(), cancel.clone());
+
+    println!("🔎 Discovery {} su {} ({} host, porte {:?})", scan_id, request.cidr, hosts.len(), ports);
+
+    let start = Instant::now();
+    let total = hosts.len();
+    let scanned = Arc::new(AtomicUsize::new(0));
+    let found = Arc::new(AtomicUsize::new(0));
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let ports = Arc::new(ports);
+    let mut tasks = Vec::new();
+
+    for ip in hosts {
+        let app = app.clone();
+        let scan_id = scan_id.clone();
+        let cancel = cancel.clone();
+        let scanned = scanned.clone();
+        let found = found.clone();
+        let semaphore = semaphore.clone();
+        let ports = ports.clone();
+
+        tasks.push(tokio::spawn(async move {
+            let _permit = semaphore.acquire_owned().await.ok();
+            if cancel.load(Ordering::Relaxed) {
+                return None;
+            }
+
+            let candidate = probe_host(ip, &ports, connect_timeout).await;
+            if let Some(candidate) = &candidate {
+                found.fetch_add(1, Ordering::Relaxed);
+                let _ = app.emit("discovery_candidate", serde_json::json!({ "scanId": scan_id, "candidate": candidate }));
+            }
+
+            let done = scanned.fetch_add(1, Ordering::Relaxed) + 1;
+            if done % PROGRESS_EVERY == 0 || done == total || candidate.is_some() {
+                let _ = app.emit("discovery_progress", serde_json::json!({
+                    "scanId": scan_id,
+                    "scanned": done,
+                    "total": total,
+                    "found": found.load(Ordering::Relaxed),
+                }));
+            }
+            candidate
+        }));
+    }
+
+    let mut candidates = Vec::new();
+    for task in tasks {
+        if let Some(candidate) = task.await.map_err(|e| format!("Errore task discovery: {}", e))? {
+            candidates.push(candidate);
+        }
+    }
+    let cancelled = cancel.load(Ordering::Relaxed);
+    DISCOVERY_SCANS.lock().unwrap().remove(&scan_id);
+
+    // Le connessioni appena tentate hanno popolato la tabella ARP
+    let arp = read_arp_table().await;
+    let existing = load_servers(app.clone()).await?;
+    let include_known = request.include_known.unwrap_or(false);
+
+    candidates.retain_mut(|candidate| {
+        candidate.mac_address = arp.get(&candidate.ip).cloned();
+        candidate.known_server_id = existing
+            .iter()
+            .find(|s| {
+                s.ip == candidate.ip
+                    || candidate.hostname.as_deref().map_or(false, |h| s.ip.eq_ignore_ascii_case(h))
+                    || (candidate.mac_address.is_some()
+                        && s.mac_address.as_deref().map(|m| m.to_uppercase().replace('-', ":")) == candidate.mac_address)
+            })
+            .map(|s| s.id.clone());
+        include_known || candidate.known_server_id.is_none()
+    });
+    candidates.sort_by_key(|c| c.ip.parse::<Ipv4Addr>().map(u32::from).unwrap_or(0));
+
+    println!("🔎 Discovery {} terminata: {} candidati{}", scan_id, candidates.len(), if cancelled { " (annullata)" } else { "" });
+    Ok(DiscoveryResult {
+        scan_id,
+        cidr: request.cidr,
+        total,
+        scanned: scanned.load(Ordering::Relaxed),
+        cancelled,
+        duration_ms: start.elapsed().as_millis() as u64,
+        candidates,
+    })
+}
+
+// ✅ COMANDO: Annulla una scansione in corso (ritorna i risultati parziali)
+#[command]
+pub fn cancel_discovery(scan_id: String) -> Result<(), String> {
+    match DISCOVERY_SCANS.lock().unwrap().get(&scan_id) {
+        Some(cancel) => {
+            cancel.store(true, Ordering::Relaxed);
+            Ok(())
+        }
+        None => Err(format!("Scansione '{}' non attiva", scan_id)),
+    }
+}
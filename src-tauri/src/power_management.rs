@@ -1,8 +1,13 @@
 // src-tauri/src/power_management.rs
 use std::net::UdpSocket;
-use std::process::{Command, Stdio};
-use tauri::command;
+use std::process::Command;
+use ssh2::Session;
+use tauri::{command, AppHandle};
 use serde::{Deserialize, Serialize};
+use crate::groups::resolve_selector;
+use crate::remote_exec::{exec_command, ExecOptions};
+use crate::ssh_session::{find_saved_target, open_session};
+use crate::Server;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PowerResult {
@@ -112,14 +117,32 @@ pub async fn wake_server(mac_address: String, broadcast_ip: Option<String>) -> R
 // ✅ SHUTDOWN: Spegnimento via SSH cross-platform
 #[command]
 pub async fn shutdown_server(
+    app: AppHandle,
     ip: String,
     ssh_user: String,
     ssh_port: u16,
     password: Option<String>,
     custom_command: Option<String>,
+    server_id: Option<String>,
 ) -> Result<PowerResult, String> {
     println!("🛑 Spegnimento server: {}@{}:{}", ssh_user, ip, ssh_port);
-    
+
+    // Server salvato (con eventuali jump host) oppure destinazione indicata dai parametri
+    let saved = find_saved_target(&app, server_id.as_deref(), &ip, ssh_port, &ssh_user).await?;
+    let (server, servers) = saved.unwrap_or_else(|| {
+        let server = Server {
+            id: format!("{}:{}", ip, ssh_port),
+            name: ip.clone(),
+            ip: ip.clone(),
+            ssh_user: ssh_user.clone(),
+            ssh_port,
+            auth_method: if password.is_some() { "password" } else { "key" }.to_string(),
+            password: password.clone(),
+            ..Default::default()
+        };
+        (server, Vec::new())
+    });
+
     // Comandi di shutdown per diversi OS
     let shutdown_commands = vec![
         custom_command.unwrap_or_else(|| "sudo shutdown -h now".to_string()),
@@ -127,150 +150,78 @@ pub async fn shutdown_server(
         "sudo halt".to_string(),
         "shutdown -s -t 0".to_string(), // Windows
     ];
-    
-    for (attempt, cmd) in shutdown_commands.iter().enumerate() {
-        println!("🔄 Tentativo {}: {}", attempt + 1, cmd);
-        
-        let result = if let Some(pwd) = &password {
-            // Con password usando sshpass (se disponibile)
-            execute_ssh_with_password(&ip, &ssh_user, ssh_port, pwd, cmd).await
-        } else {
-            // Con chiave SSH
-            execute_ssh_with_key(&ip, &ssh_user, ssh_port, cmd).await
-        };
-        
-        match result {
-            Ok(success_msg) => {
-                return Ok(PowerResult {
-                    success: true,
-                    message: success_msg,
-                    details: Some(format!("Comando eseguito: {}", cmd)),
-                });
+
+    tokio::task::spawn_blocking(move || {
+        let session = match open_session(&server, &servers) {
+            Ok(session) => session,
+            Err(e) => return PowerResult {
+                success: false,
+                message: "Impossibile connettersi al server".to_string(),
+                details: Some(e),
             },
-            Err(e) => {
-                println!("⚠️ Tentativo {} fallito: {}", attempt + 1, e);
-                if attempt == shutdown_commands.len() - 1 {
-                    return Ok(PowerResult {
-                        success: false,
-                        message: "Impossibile spegnere il server".to_string(),
-                        details: Some(format!("Ultimo errore: {}", e)),
-                    });
+        };
+
+        for (attempt, cmd) in shutdown_commands.iter().enumerate() {
+            println!("🔄 Tentativo {}: {}", attempt + 1, cmd);
+
+            match execute_shutdown_command(&session, &server, cmd) {
+                Ok(success_msg) => {
+                    return PowerResult {
+                        success: true,
+                        message: success_msg,
+                        details: Some(format!("Comando eseguito: {}", cmd)),
+                    };
+                },
+                Err(e) => {
+                    println!("⚠️ Tentativo {} fallito: {}", attempt + 1, e);
+                    if attempt == shutdown_commands.len() - 1 {
+                        return PowerResult {
+                            success: false,
+                            message: "Impossibile spegnere il server".to_string(),
+                            details: Some(format!("Ultimo errore: {}", e)),
+                        };
+                    }
                 }
             }
         }
-    }
-    
-    Ok(PowerResult {
-        success: false,
-        message: "Tutti i tentativi di spegnimento sono falliti".to_string(),
-        details: None,
-    })
-}
 
-// ✅ Helper: SSH con password
-async fn execute_ssh_with_password(
-    ip: &str,
-    user: &str,
-    port: u16,
-    password: &str,
-    command: &str,
-) -> Result<String, String> {
-    // Prova prima sshpass (Linux/Mac)
-    let mut cmd = Command::new("sshpass");
-    cmd.arg("-p").arg(password)
-       .arg("ssh")
-       .arg("-o").arg("StrictHostKeyChecking=no")
-       .arg("-o").arg("ConnectTimeout=10")
-       .arg("-p").arg(port.to_string())
-       .arg(format!("{}@{}", user, ip))
-       .arg(command)
-       .stdout(Stdio::piped())
-       .stderr(Stdio::piped());
-    
-    match cmd.output() {
-        Ok(output) => {
-            if output.status.success() {
-                Ok("Comando spegnimento eseguito con successo".to_string())
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                
-                // Se sshpass non è disponibile, prova metodo alternativo
-                if stderr.contains("command not found") || stderr.contains("No such file") {
-                    return execute_ssh_expect(ip, user, port, password, command).await;
-                }
-                
-                Err(format!("SSH error: {}", stderr))
-            }
-        },
-        Err(_) => {
-            // sshpass non disponibile, prova expect
-            execute_ssh_expect(ip, user, port, password, command).await
+        PowerResult {
+            success: false,
+            message: "Tutti i tentativi di spegnimento sono falliti".to_string(),
+            details: None,
         }
-    }
+    })
+    .await
+    .map_err(|e| format!("Errore task spegnimento: {}", e))
 }
 
-// ✅ Helper: SSH con expect (fallback per password)
-async fn execute_ssh_expect(
-    ip: &str,
-    user: &str,
-    port: u16,
-    password: &str,
-    command: &str,
-) -> Result<String, String> {
-    // Script expect per automazione password
-    let expect_script = format!(r#"
-spawn ssh -o StrictHostKeyChecking=no -p {} {}@{} "{}"
-expect {{
-    "password:" {{ send "{}\r"; exp_continue }}
-    "Password:" {{ send "{}\r"; exp_continue }}
-    eof
-}}
-"#, port, user, ip, command, password, password);
-    
-    let mut cmd = Command::new("expect");
-    cmd.arg("-c").arg(&expect_script)
-       .stdout(Stdio::piped())
-       .stderr(Stdio::piped());
-    
-    match cmd.output() {
-        Ok(output) => {
-            if output.status.success() {
-                Ok("Comando spegnimento eseguito (expect)".to_string())
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                Err(format!("Expect error: {}", stderr))
-            }
-        },
-        Err(e) => Err(format!("Expect non disponibile: {}", e)),
-    }
-}
+// ✅ Helper: esegue un comando di spegnimento tramite il livello di esecuzione remota
+// Il prefisso "sudo" usa sudo -S con la password del server
+fn execute_shutdown_command(session: &Session, server: &Server, command: &str) -> Result<String, String> {
+    let (sudo, command) = match command.strip_prefix("sudo ") {
+        Some(rest) => (true, rest.trim()),
+        None => (false, command),
+    };
 
-// ✅ Helper: SSH con chiave
-async fn execute_ssh_with_key(
-    ip: &str,
-    user: &str,
-    port: u16,
-    command: &str,
-) -> Result<String, String> {
-    let mut cmd = Command::new("ssh");
-    cmd.arg("-o").arg("StrictHostKeyChecking=no")
-       .arg("-o").arg("ConnectTimeout=10")
-       .arg("-p").arg(port.to_string())
-       .arg(format!("{}@{}", user, ip))
-       .arg(command)
-       .stdout(Stdio::piped())
-       .stderr(Stdio::piped());
-    
-    match cmd.output() {
-        Ok(output) => {
-            if output.status.success() {
-                Ok("Comando spegnimento eseguito con chiave SSH".to_string())
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                Err(format!("SSH key error: {}", stderr))
-            }
-        },
-        Err(e) => Err(format!("SSH execution error: {}", e)),
+    let options = ExecOptions {
+        timeout_secs: Some(20),
+        sudo: Some(sudo),
+        ..Default::default()
+    };
+
+    let result = exec_command(session, server, command, &options, |_, _| {})?;
+
+    // Se la connessione cade subito dopo il comando, il server si sta spegnendo
+    if result.success() || result.connection_lost {
+        Ok("Comando spegnimento eseguito con successo".to_string())
+    } else if result.timed_out {
+        Err("Timeout in attesa del comando di spegnimento".to_string())
+    } else {
+        Err(format!(
+            "Exit code {}: {}",
+            result.exit_code.map(|c| c.to_string()).unwrap_or_else(|| "?".to_string()),
+            result.stderr.trim()
+        ))
     }
 }
 
@@ -309,4 +260,72 @@ pub async fn test_network_connectivity(target_ip: String) -> Result<PowerResult,
             details: Some(format!("Comando ping error: {}", e)),
         }),
     }
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkPowerResult {
+    pub server_id: String,
+    pub server_name: String,
+    pub result: PowerResult,
+}
+
+// ✅ AZIONI DI MASSA: wake / shutdown su tutti i server di un selettore (tag, gruppi...)
+#[command]
+pub async fn bulk_power_action(app: AppHandle, selector: String, action: String) -> Result<Vec<BulkPowerResult>, String> {
+    if action != "wake" && action != "shutdown" {
+        return Err(format!("Azione non supportata: {}", action));
+    }
+
+    let targets = resolve_selector(&app, &selector).await?;
+    if targets.is_empty() {
+        return Err("Nessun server corrisponde al selettore".to_string());
+    }
+    println!("⚡ {} su {} server ({})", action, targets.len(), selector);
+
+    let mut tasks = Vec::new();
+    for server in targets {
+        let app = app.clone();
+        let action = action.clone();
+
+        tasks.push(tokio::spawn(async move {
+            let result = if action == "wake" {
+                match server.mac_address.clone().filter(|m| !m.trim().is_empty()) {
+                    Some(mac) if server.wol_enabled.unwrap_or(true) => wake_server(mac, None).await,
+                    _ => Ok(PowerResult {
+                        success: false,
+                        message: "Wake-on-LAN non configurato".to_string(),
+                        details: Some("MAC address mancante o WoL disabilitato".to_string()),
+                    }),
+                }
+            } else {
+                shutdown_server(
+                    app,
+                    server.ip.clone(),
+                    server.ssh_user.clone(),
+                    server.ssh_port,
+                    server.password.clone(),
+                    server.shutdown_command.clone(),
+                    Some(server.id.clone()),
+                )
+                .await
+            };
+
+            BulkPowerResult {
+                server_id: server.id,
+                server_name: server.name,
+                result: result.unwrap_or_else(|e| PowerResult {
+                    success: false,
+                    message: "Errore".to_string(),
+                    details: Some(e),
+                }),
+            }
+        }));
+    }
+
+    let mut results = Vec::new();
+    for task in tasks {
+        results.push(task.await.map_err(|e| format!("Errore task: {}", e))?);
+    }
+    Ok(results)
+}
@@ -0,0 +1,494 @@
+// src-tauri/src/remote_exec.rs
+// Esecuzione di comandi remoti via ssh2 con risultato strutturato
+// stdout/stderr separati, exit code, durata, timeout, sudo e output in streaming
+
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use ssh2::Session;
+use tauri::{command, AppHandle, Emitter};
+
+use crate::new_id;
+use crate::ssh_session::{find_server, open_session, take_utf8};
+use crate::Server;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecOptions {
+    pub timeout_secs: Option<u64>,       // default 60s, 0 = nessun limite
+    pub sudo: Option<bool>,
+    pub sudo_password: Option<String>,   // default: password del server se auth "password"
+    pub stream: Option<bool>,            // emette "remote_command_output" durante l'esecuzione
+    pub execution_id: Option<String>,
+    pub kill_on_timeout: Option<bool>,   // opt-in: il comando gira in `sh -c` e allo scadere del timeout viene terminato
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteCommandResult {
+    pub execution_id: String,
+    pub server_id: String,
+    pub command: String,
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    pub timed_out: bool,
+    pub connection_lost: bool,
+}
+
+impl RemoteCommandResult {
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0) && !self.timed_out
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteOutputEvent {
+    pub execution_id: String,
+    pub server_id: String,
+    pub stream: String,                  // "stdout" | "stderr"
+    pub data: String,
+}
+
+// ✅ COMANDO: Esegue un comando su un server salvato
+#[command]
+pub async fn run_remote_command(
+    app: AppHandle,
+    server_id: String,
+    command: String,
+    options: Option<ExecOptions>,
+) -> Result<RemoteCommandResult, String> {
+    let (server, servers) = find_server(&app, &server_id).await?;
+    execute_on_server(&app, server, servers, command, options.unwrap_or_default()).await
+}
+
+// ✅ Esecuzione riutilizzabile dagli altri moduli (broadcast, metriche, docker...)
+pub async fn execute_on_server(
+    app: &AppHandle,
+    server: Server,
+    servers: Vec<Server>,
+    command: String,
+    options: ExecOptions,
+) -> Result<RemoteCommandResult, String> {
+    let app = app.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let session = open_session(&server, &servers)?;
+        exec_with_events(&app, &session, &server, &command, &options)
+    })
+    .await
+    .map_err(|e| format!("Errore task comando remoto: {}", e))?
+}
+
+// Variante bloccante su una sessione già aperta, con eventi di streaming se richiesti
+pub fn exec_with_events(
+    app: &AppHandle,
+    session: &Session,
+    server: &Server,
+    command: &str,
+    options: &ExecOptions,
+) -> Result<RemoteCommandResult, String> {
+    let execution_id = options.execution_id.clone().unwrap_or_else(|| new_id("exec"));
+    let stream = options.stream.unwrap_or(false);
+
+    println!("▶️ [{}] {}@{}: {}", execution_id, server.ssh_user, server.ip, command);
+
+    let mut result = exec_command(session, server, command, options, |stream_name, data| {
+        if stream {
+            let _ = app.emit("remote_command_output", RemoteOutputEvent {
+                execution_id: execution_id.clone(),
+                server_id: server.id.clone(),
+                stream: stream_name.to_string(),
+                data: data.to_string(),
+            });
+        }
+    })?;
+
+    result.execution_id = execution_id;
+    Ok(result)
+}
+
+// ✅ Esegue il comando su una sessione aperta
+// Lettura non bloccante di stdout e stderr per rispettare timeout e streaming
+pub fn exec_command(
+    session: &Session,
+    server: &Server,
+    command: &str,
+    options: &ExecOptions,
+    mut on_output: impl FnMut(&str, &str),
+) -> Result<RemoteCommandResult, String> {
+    let sudo = options.sudo.unwrap_or(false);
+    let sudo_password = options.sudo_password.clone().or_else(|| {
+        if server.auth_method == "password" { server.password.clone() } else { None }
+    });
+
+    // Con sudo il prompt è un marcatore su stderr: la password si invia solo se sudo la chiede davvero
+    // (NOPASSWD o timestamp in cache non leggono stdin e il comando la riceverebbe in chiaro);
+    // il secondo marcatore, stampato dalla shell lanciata da sudo, indica che l'autenticazione è finita
+    let mut sudo_markers = sudo.then(|| {
+        let id = new_id("sudo");
+        (format!("[{}-prompt]", id), format!("[{}-ready]", id))
+    });
+    let full_command = match &sudo_markers {
+        Some((prompt, ready)) => format!(
+            "sudo -S -p {} sh -c {}",
+            shell_quote(prompt),
+            shell_quote(&format!("printf %s {} >&2; {}", shell_quote(ready), command))
+        ),
+        None => command.to_string(),
+    };
+    let kill_on_timeout = options.kill_on_timeout.unwrap_or(false);
+    let pid_file = remote_pid_file();
+    let full_command = if kill_on_timeout {
+        with_pid_file(&full_command, &pid_file)
+    } else {
+        full_command
+    };
+
+    let timeout = match options.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS) {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    };
+
+    let start = Instant::now();
+    let mut channel = session
+        .channel_session()
+        .map_err(|e| format!("Errore apertura canale: {}", e))?;
+    channel
+        .exec(&full_command)
+        .map_err(|e| format!("Errore esecuzione comando: {}", e))?;
+
+    if sudo_markers.is_none() {
+        let _ = channel.send_eof();
+    }
+
+    let mut stdout: Vec<u8> = Vec::new();
+    let mut stderr: Vec<u8> = Vec::new();
+    // Byte non ancora emessi: un carattere UTF-8 può arrivare spezzato tra due letture
+    let mut pending_out: Vec<u8> = Vec::new();
+    let mut pending_err: Vec<u8> = Vec::new();
+    let mut buf = [0u8; 8 * 1024];
+    let mut timed_out = false;
+    let mut connection_lost = false;
+    let mut stderr_stream = channel.stderr();
+    // stderr trattenuto finché sudo non ha finito di autenticare (contiene i marcatori)
+    let mut sudo_stderr: Vec<u8> = Vec::new();
+    let mut password_sent = false;
+
+    session.set_blocking(false);
+    loop {
+        let mut progressed = false;
+
+        match channel.read(&mut buf) {
+            Ok(0) => {}
+            Ok(n) => {
+                pending_out.extend_from_slice(&buf[..n]);
+                let text = take_utf8(&mut pending_out);
+                if !text.is_empty() {
+                    on_output("stdout", &text);
+                }
+                stdout.extend_from_slice(&buf[..n]);
+                progressed = true;
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(_) => {
+                connection_lost = true;
+                break;
+            }
+        }
+
+        match stderr_stream.read(&mut buf) {
+            Ok(0) => {}
+            Ok(n) if sudo_markers.is_some() => {
+                progressed = true;
+                sudo_stderr.extend_from_slice(&buf[..n]);
+                let (prompt, ready) = sudo_markers.as_ref().unwrap();
+
+                while let Some(pos) = find_bytes(&sudo_stderr, prompt.as_bytes()) {
+                    sudo_stderr.drain(pos..pos + prompt.len());
+                    match (&sudo_password, password_sent) {
+                        (Some(password), false) => {
+                            session.set_blocking(true);
+                            let _ = channel.write_all(format!("{}\n", password).as_bytes());
+                            session.set_blocking(false);
+                            password_sent = true;
+                        }
+                        // Nessuna password o password rifiutata: EOF e sudo termina con errore
+                        _ => {
+                            let _ = channel.send_eof();
+                        }
+                    }
+                }
+
+                if let Some(pos) = find_bytes(&sudo_stderr, ready.as_bytes()) {
+                    sudo_stderr.drain(pos..pos + ready.len());
+                    let _ = channel.send_eof();
+                    sudo_markers = None;
+                    pending_err.append(&mut sudo_stderr);
+                    stderr.extend_from_slice(&pending_err);
+                    let text = take_utf8(&mut pending_err);
+                    if !text.is_empty() {
+                        on_output("stderr", &text);
+                    }
+                }
+            }
+            Ok(n) => {
+                pending_err.extend_from_slice(&buf[..n]);
+                let text = take_utf8(&mut pending_err);
+                if !text.is_empty() {
+                    on_output("stderr", &text);
+                }
+                stderr.extend_from_slice(&buf[..n]);
+                progressed = true;
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(_) => {
+                connection_lost = true;
+                break;
+            }
+        }
+
+        if !progressed && channel.eof() {
+            break;
+        }
+
+        if timeout.map_or(false, |t| start.elapsed() >= t) {
+            timed_out = true;
+            break;
+        }
+
+        if !progressed {
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+    session.set_blocking(true);
+
+    // sudo non è arrivato al comando (password errata o assente): il suo stderr va comunque mostrato
+    if !sudo_stderr.is_empty() {
+        stderr.extend_from_slice(&sudo_stderr);
+        pending_err.append(&mut sudo_stderr);
+    }
+    for (name, pending) in [("stdout", &pending_out), ("stderr", &pending_err)] {
+        if !pending.is_empty() {
+            on_output(name, &String::from_utf8_lossy(pending));
+        }
+    }
+
+    let exit_code = if timed_out || connection_lost {
+        let _ = channel.close();
+        if timed_out && kill_on_timeout {
+            kill_remote_command(session, &pid_file);
+        }
+        None
+    } else {
+        session.set_timeout(5_000);
+        let closed = channel.wait_close();
+        session.set_timeout(0);
+        closed.ok().and_then(|_| channel.exit_status().ok())
+    };
+
+    Ok(RemoteCommandResult {
+        execution_id: String::new(),
+        server_id: server.id.clone(),
+        command: command.to_string(),
+        stdout: String::from_utf8_lossy(&stdout).to_string(),
+        stderr: String::from_utf8_lossy(&stderr).to_string(),
+        exit_code,
+        duration_ms: start.elapsed().as_millis() as u64,
+        timed_out,
+        connection_lost,
+    })
+}
+
+// File in /tmp del server con il PID della shell che esegue il comando
+fn remote_pid_file() -> String {
+    format!("/tmp/.devpulse-{}.pid", new_id("exec"))
+}
+
+// Esegue il comando in `sh -c` (qualunque sia la shell di login) salvando il gruppo di processi
+// aperto da sshd: serve per terminare il comando e i figli (senza pty chiudere il canale non li ferma)
+fn with_pid_file(command: &str, pid_file: &str) -> String {
+    let script = format!(
+        "PGID=$(ps -o pgid= -p $$ 2>/dev/null | tr -d ' '); echo ${{PGID:-$$}} > {0}; trap 'rm -f {0}' EXIT; {1}",
+        pid_file, command
+    );
+    format!("sh -c {}", shell_quote(&script))
+}
+
+// Termina il gruppo di processi del comando (TERM, poi KILL) su un nuovo canale della stessa sessione
+// Se il gruppo non è stato rilevato si termina almeno la shell del comando
+fn kill_remote_command(session: &Session, pid_file: &str) {
+    let script = format!(
+        "PID=$(cat {0} 2>/dev/null); if [ -n \"$PID\" ]; then kill -TERM -- -$PID 2>/dev/null || kill -TERM $PID 2>/dev/null; sleep 2; kill -KILL -- -$PID 2>/dev/null || kill -KILL $PID 2>/dev/null; fi; rm -f {0}",
+        pid_file
+    );
+    let script = format!("sh -c {}", shell_quote(&script));
+
+    session.set_timeout(5_000);
+    if let Ok(mut channel) = session.channel_session() {
+        if channel.exec(&script).is_ok() {
+            let _ = channel.wait_close();
+        }
+    }
+    session.set_timeout(0);
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+// Quoting POSIX per passare il comando a `sh -c`
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+// ✅ Divide l'output di uno script in sezioni marcate da righe "==NOME=="
+// (usato dalle sonde che raccolgono più informazioni in un solo round-trip)
+pub fn split_output_sections(output: &str) -> HashMap<String, Vec<String>> {
+    let mut sections: HashMap<String, Vec<String>> = HashMap::new();
+    let mut current = String::new();
+
+    for line in output.lines() {
+        let line = line.trim_end();
+        if line.len() > 4 && line.starts_with("==") && line.ends_with("==") {
+            current = line.trim_matches('=').to_string();
+            sections.entry(current.clone()).or_default();
+        } else if !current.is_empty() && !line.trim().is_empty() {
+            sections.entry(current.clone()).or_default().push(line.to_string());
+        }
+    }
+
+    sections
+}
+
+static FOLLOW_STREAMS: OnceCell<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceCell::new();
+
+// ✅ Avvia un comando "infinito" (tail -f, docker logs -f, journalctl -f...) in un thread
+// e consegna l'output riga per riga; si ferma con stop_remote_stream o a fine comando
+pub fn spawn_follow<L, E>(
+    server: Server,
+    servers: Vec<Server>,
+    command: String,
+    stream_id: String,
+    mut on_line: L,
+    on_end: E,
+) where
+    L: FnMut(&str, &str) + Send + 'static,
+    E: FnOnce(Option<String>) + Send + 'static,
+{
+    let stop = Arc::new(AtomicBool::new(false));
+    FOLLOW_STREAMS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap()
+        .insert(stream_id.clone(), stop.clone());
+
+    thread::spawn(move || {
+        let result = open_session(&server, &servers)
+            .and_then(|session| follow_command(&session, &command, &stop, &mut on_line));
+
+        if let Some(lock) = FOLLOW_STREAMS.get() {
+            lock.lock().unwrap().remove(&stream_id);
+        }
+        println!("⏹️ Stream {} terminato", stream_id);
+        on_end(result.err());
+    });
+}
+
+// ✅ COMANDO: Ferma uno stream remoto (log, journal, docker logs...)
+#[command]
+pub fn stop_remote_stream(stream_id: String) -> Result<(), String> {
+    let streams = FOLLOW_STREAMS.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap();
+    match streams.get(&stream_id) {
+        Some(stop) => {
+            stop.store(true, Ordering::Relaxed);
+            Ok(())
+        }
+        None => Err(format!("Stream '{}' non attivo", stream_id)),
+    }
+}
+
+fn follow_command(
+    session: &Session,
+    command: &str,
+    stop: &AtomicBool,
+    on_line: &mut impl FnMut(&str, &str),
+) -> Result<(), String> {
+    let pid_file = remote_pid_file();
+    let mut channel = session
+        .channel_session()
+        .map_err(|e| format!("Errore apertura canale: {}", e))?;
+    channel
+        .exec(&with_pid_file(command, &pid_file))
+        .map_err(|e| format!("Errore esecuzione comando: {}", e))?;
+    let _ = channel.send_eof();
+
+    let mut stderr_stream = channel.stderr();
+    let mut pending_out: Vec<u8> = Vec::new();
+    let mut pending_err: Vec<u8> = Vec::new();
+    let mut buf = [0u8; 8 * 1024];
+
+    session.set_blocking(false);
+    while !stop.load(Ordering::Relaxed) {
+        let mut progressed = false;
+
+        match channel.read(&mut buf) {
+            Ok(0) => {}
+            Ok(n) => {
+                progressed = true;
+                push_lines(&mut pending_out, &buf[..n], "stdout", on_line);
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(e) => return Err(format!("Connessione persa: {}", e)),
+        }
+
+        match stderr_stream.read(&mut buf) {
+            Ok(0) => {}
+            Ok(n) => {
+                progressed = true;
+                push_lines(&mut pending_err, &buf[..n], "stderr", on_line);
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(e) => return Err(format!("Connessione persa: {}", e)),
+        }
+
+        if !progressed && channel.eof() {
+            break;
+        }
+        if !progressed {
+            thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    session.set_blocking(true);
+
+    for (name, pending) in [("stdout", &pending_out), ("stderr", &pending_err)] {
+        if !pending.is_empty() {
+            on_line(name, String::from_utf8_lossy(pending).trim_end());
+        }
+    }
+
+    let _ = channel.close();
+    if stop.load(Ordering::Relaxed) {
+        kill_remote_command(session, &pid_file);
+    }
+    Ok(())
+}
+
+// Le righe si decodificano solo quando sono complete: un carattere UTF-8 può arrivare spezzato tra due letture
+fn push_lines(pending: &mut Vec<u8>, data: &[u8], stream: &str, on_line: &mut impl FnMut(&str, &str)) {
+    pending.extend_from_slice(data);
+    while let Some(pos) = pending.iter().position(|&b| b == b'\n') {
+        let line: Vec<u8> = pending.drain(..=pos).collect();
+        on_line(stream, String::from_utf8_lossy(&line).trim_end_matches(['\r', '\n']));
+    }
+}
@@ -0,0 +1,521 @@
+// src-tauri/src/runbooks.rs
+// Runbook: procedure multi-step (JSON o YAML) eseguite in ordine dal motore Rust
+// Step: comando remoto, azione di alimentazione, attesa porta, comando locale, conferma manuale
+// Log per step via eventi, stop condizionale sui fallimenti e modalità dry-run
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Emitter};
+use tokio::sync::oneshot;
+
+use crate::power_management::{shutdown_server, wake_server};
+use crate::remote_exec::{execute_on_server, ExecOptions};
+use crate::snapshots::snapshot_before_change;
+use crate::ssh_session::find_server;
+use crate::{new_id, read_json_file, write_json_file};
+
+const RUNBOOKS_FILE: &str = "runbooks.json";
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_wait_timeout() -> u64 {
+    300
+}
+
+fn default_wait_interval() -> u64 {
+    5
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Runbook {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "default_true")]
+    pub stop_on_failure: bool,
+    pub steps: Vec<RunbookStep>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RunbookStep {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub continue_on_failure: bool,       // ignora il fallimento di questo step anche con stopOnFailure
+    #[serde(flatten)]
+    pub action: StepAction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StepAction {
+    #[serde(rename_all = "camelCase")]
+    RemoteCommand {
+        server_id: String,
+        command: String,
+        #[serde(default)]
+        sudo: bool,
+        timeout_secs: Option<u64>,
+    },
+    #[serde(rename_all = "camelCase")]
+    PowerAction {
+        server_id: String,
+        action: String,                  // "wake" | "shutdown" | "reboot"
+    },
+    #[serde(rename_all = "camelCase")]
+    WaitForPort {
+        server_id: Option<String>,       // default: ip e porta SSH del server
+        host: Option<String>,
+        port: Option<u16>,
+        #[serde(default = "default_wait_timeout")]
+        timeout_secs: u64,
+        #[serde(default = "default_wait_interval")]
+        interval_secs: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    LocalCommand {
+        command: String,
+        timeout_secs: Option<u64>,
+    },
+    #[serde(rename_all = "camelCase")]
+    ManualConfirmation {
+        message: String,
+    },
+}
+
+impl StepAction {
+    fn kind(&self) -> &'static str {
+        match self {
+            StepAction::RemoteCommand { .. } => "remote_command",
+            StepAction::PowerAction { .. } => "power_action",
+            StepAction::WaitForPort { .. } => "wait_for_port",
+            StepAction::LocalCommand { .. } => "local_command",
+            StepAction::ManualConfirmation { .. } => "manual_confirmation",
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StepResult {
+    pub index: usize,
+    pub name: String,
+    pub kind: String,
+    pub status: String,                  // "success" | "failed" | "skipped" | "dry_run" | "cancelled"
+    pub logs: Vec<String>,
+    pub started_at: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RunbookRunResult {
+    pub run_id: String,
+    pub runbook_id: String,
+    pub dry_run: bool,
+    pub success: bool,
+    pub stopped_at: Option<usize>,
+    pub steps: Vec<StepResult>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RunbookLogEvent {
+    pub run_id: String,
+    pub step_index: usize,
+    pub line: String,
+}
+
+struct RunControl {
+    cancelled: Arc<AtomicBool>,
+    confirmation: Option<oneshot::Sender<bool>>,
+}
+
+static RUNBOOK_RUNS: OnceCell<Mutex<HashMap<String, RunControl>>> = OnceCell::new();
+
+fn runs() -> &'static Mutex<HashMap<String, RunControl>> {
+    RUNBOOK_RUNS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// ✅ Parsing di un runbook: JSON se inizia con "{", altrimenti YAML
+pub fn parse_runbook(content: &str) -> Result<Runbook, String> {
+    let runbook: Runbook = if content.trim_start().starts_with('{') {
+        serde_json::from_str(content).map_err(|e| format!("Runbook JSON non valido: {}", e))?
+    } else {
+        serde_yaml::from_str(content).map_err(|e| format!("Runbook YAML non valido: {}", e))?
+    };
+
+    if runbook.name.trim().is_empty() {
+        return Err("Il nome del runbook è obbligatorio".to_string());
+    }
+    if runbook.steps.is_empty() {
+        return Err("Il runbook non contiene step".to_string());
+    }
+    Ok(runbook)
+}
+
+fn load_runbooks(app: &AppHandle) -> Result<Vec<Runbook>, String> {
+    read_json_file(app, RUNBOOKS_FILE)
+}
+
+// ✅ COMANDO: Elenco runbook salvati
+#[command]
+pub async fn list_runbooks(app: AppHandle) -> Result<Vec<Runbook>, String> {
+    load_runbooks(&app)
+}
+
+// ✅ COMANDO: Salva un runbook (oggetto o testo JSON/YAML); id vuoto = nuovo
+#[command]
+pub async fn save_runbook(app: AppHandle, runbook: Option<Runbook>, content: Option<String>) -> Result<Runbook, String> {
+    let mut runbook = match (runbook, content) {
+        (Some(runbook), _) => runbook,
+        (None, Some(content)) => parse_runbook(&content)?,
+        (None, None) => return Err("Nessun runbook fornito".to_string()),
+    };
+    if runbook.steps.is_empty() {
+        return Err("Il runbook non contiene step".to_string());
+    }
+
+    let mut runbooks = load_runbooks(&app)?;
+    match runbooks.iter_mut().find(|r| !runbook.id.is_empty() && r.id == runbook.id) {
+        Some(existing) => *existing = runbook.clone(),
+        None => {
+            if runbook.id.is_empty() {
+                runbook.id = new_id("runbook");
+            }
+            runbooks.push(runbook.clone());
+        }
+    }
+
+    snapshot_before_change(&app, "salvataggio runbook")?;
+    write_json_file(&app, RUNBOOKS_FILE, &runbooks)?;
+    Ok(runbook)
+}
+
+// ✅ COMANDO: Elimina un runbook
+#[command]
+pub async fn delete_runbook(app: AppHandle, runbook_id: String) -> Result<(), String> {
+    let mut runbooks = load_runbooks(&app)?;
+    let before = runbooks.len();
+    runbooks.retain(|r| r.id != runbook_id);
+    if runbooks.len() == before {
+        return Err(format!("Runbook '{}' non trovato", runbook_id));
+    }
+    snapshot_before_change(&app, "eliminazione runbook")?;
+    write_json_file(&app, RUNBOOKS_FILE, &runbooks)
+}
+
+// ✅ COMANDO: Esegue un runbook (o ne stampa il piano con dry_run)
+// Eventi: "runbook_step_started", "runbook_step_log", "runbook_step_finished"
+#[command]
+pub async fn run_runbook(
+    app: AppHandle,
+    runbook_id: String,
+    dry_run: Option<bool>,
+    run_id: Option<String>,
+) -> Result<RunbookRunResult, String> {
+    let runbook = load_runbooks(&app)?
+        .into_iter()
+        .find(|r| r.id == runbook_id)
+        .ok_or_else(|| format!("Runbook '{}' non trovato", runbook_id))?;
+
+    let dry_run = dry_run.unwrap_or(false);
+    let run_id = run_id.unwrap_or_else(|| new_id("run"));
+    let cancelled = Arc::new(AtomicBool::new(false));
+    runs().lock().unwrap().insert(run_id.clone(), RunControl { cancelled: cancelled.clone(), confirmation: None });
+
+    println!("📘 Runbook '{}' ({} step){}", runbook.name, runbook.steps.len(), if dry_run { " [dry-run]" } else { "" });
+
+    let mut steps = Vec::new();
+    let mut stopped_at = None;
+    let mut success = true;
+
+    for (index, step) in runbook.steps.iter().enumerate() {
+        let name = step.name.clone().unwrap_or_else(|| format!("Step {}", index + 1));
+        let started = Instant::now();
+        let started_at = chrono::Local::now().to_rfc3339();
+        let _ = app.emit("runbook_step_started", serde_json::json!({
+            "runId": run_id, "stepIndex": index, "name": name, "kind": step.action.kind(),
+        }));
+
+        let mut logs: Vec<String> = Vec::new();
+        let mut log = |line: String| {
+            let _ = app.emit("runbook_step_log", RunbookLogEvent { run_id: run_id.clone(), step_index: index, line: line.clone() });
+            logs.push(line);
+        };
+
+        let status = if stopped_at.is_some() {
+            "skipped"
+        } else if cancelled.load(Ordering::Relaxed) {
+            "cancelled"
+        } else if dry_run {
+            match describe_step(&app, &step.action).await {
+                Ok(description) => log(description),
+                Err(e) => {
+                    log(format!("❌ {}", e));
+                    success = false;
+                }
+            }
+            "dry_run"
+        } else {
+            match run_step(&app, &run_id, &step.action, &cancelled, &mut log).await {
+                Ok(()) => "success",
+                Err(e) => {
+                    log(format!("❌ {}", e));
+                    if cancelled.load(Ordering::Relaxed) { "cancelled" } else { "failed" }
+                }
+            }
+        };
+
+        if status == "cancelled" && stopped_at.is_none() {
+            stopped_at = Some(index);
+            success = false;
+        } else if status == "failed" {
+            success = false;
+            if runbook.stop_on_failure && !step.continue_on_failure {
+                stopped_at = Some(index);
+            }
+        }
+
+        let result = StepResult {
+            index,
+            name,
+            kind: step.action.kind().to_string(),
+            status: status.to_string(),
+            logs,
+            started_at,
+            duration_ms: started.elapsed().as_millis() as u64,
+        };
+        let _ = app.emit("runbook_step_finished", serde_json::json!({ "runId": run_id, "step": result }));
+        steps.push(result);
+    }
+
+    runs().lock().unwrap().remove(&run_id);
+    println!("📘 Runbook '{}' terminato: {}", runbook.name, if success { "ok" } else { "fallito" });
+
+    Ok(RunbookRunResult { run_id, runbook_id, dry_run, success, stopped_at, steps })
+}
+
+// ✅ COMANDO: Risponde a uno step di conferma manuale in attesa
+#[command]
+pub fn confirm_runbook_step(run_id: String, approved: bool) -> Result<(), String> {
+    let mut runs = runs().lock().unwrap();
+    let control = runs.get_mut(&run_id).ok_or_else(|| format!("Esecuzione '{}' non attiva", run_id))?;
+    let sender = control.confirmation.take().ok_or("Nessuna conferma in attesa")?;
+    let _ = sender.send(approved);
+    Ok(())
+}
+
+// ✅ COMANDO: Annulla un runbook in esecuzione (gli step successivi non partono)
+#[command]
+pub fn cancel_runbook(run_id: String) -> Result<(), String> {
+    let mut runs = runs().lock().unwrap();
+    let control = runs.get_mut(&run_id).ok_or_else(|| format!("Esecuzione '{}' non attiva", run_id))?;
+    control.cancelled.store(true, Ordering::Relaxed);
+    if let Some(sender) = control.confirmation.take() {
+        let _ = sender.send(false);
+    }
+    Ok(())
+}
+
+// Piano dello step per il dry-run (verifica anche che i server esistano)
+async fn describe_step(app: &AppHandle, action: &StepAction) -> Result<String, String> {
+    Ok(match action {
+        StepAction::RemoteCommand { server_id, command, sudo, .. } => {
+            let (server, _) = find_server(app, server_id).await?;
+            format!("Eseguirebbe{} `{}` su {} ({})", if *sudo { " con sudo" } else { "" }, command, server.name, server.ip)
+        }
+        StepAction::PowerAction { server_id, action } => {
+            let (server, _) = find_server(app, server_id).await?;
+            validate_power_action(action)?;
+            format!("Eseguirebbe l'azione '{}' su {} ({})", action, server.name, server.ip)
+        }
+        StepAction::WaitForPort { timeout_secs, .. } => {
+            let (host, port) = wait_target(app, action).await?;
+            format!("Attenderebbe {}:{} per al massimo {}s", host, port, timeout_secs)
+        }
+        StepAction::LocalCommand { command, .. } => format!("Eseguirebbe in locale `{}`", command),
+        StepAction::ManualConfirmation { message } => format!("Chiederebbe conferma: {}", message),
+    })
+}
+
+fn validate_power_action(action: &str) -> Result<(), String> {
+    match action {
+        "wake" | "shutdown" | "reboot" => Ok(()),
+        other => Err(format!("Azione di alimentazione non supportata: {}", other)),
+    }
+}
+
+async fn wait_target(app: &AppHandle, action: &StepAction) -> Result<(String, u16), String> {
+    let StepAction::WaitForPort { server_id, host, port, .. } = action else {
+        return Err("Step non di attesa".to_string());
+    };
+
+    let server = match server_id {
+        Some(id) => Some(find_server(app, id).await?.0),
+        None => None,
+    };
+    let host = host
+        .clone()
+        .or_else(|| server.as_ref().map(|s| s.ip.clone()))
+        .ok_or("Host o server da attendere non specificato")?;
+    let port = port.or_else(|| server.as_ref().map(|s| s.ssh_port)).unwrap_or(22);
+    Ok((host, port))
+}
+
+async fn run_step(
+    app: &AppHandle,
+    run_id: &str,
+    action: &StepAction,
+    cancelled: &AtomicBool,
+    log: &mut impl FnMut(String),
+) -> Result<(), String> {
+    match action {
+        StepAction::RemoteCommand { server_id, command, sudo, timeout_secs } => {
+            let (server, servers) = find_server(app, server_id).await?;
+            log(format!("▶️ {} su {}", command, server.name));
+            let options = ExecOptions {
+                timeout_secs: *timeout_secs,
+                sudo: Some(*sudo),
+                ..Default::default()
+            };
+            let result = execute_on_server(app, server, servers, command.clone(), options).await?;
+            for line in result.stdout.lines().chain(result.stderr.lines()) {
+                log(line.to_string());
+            }
+            if result.success() {
+                Ok(())
+            } else if result.timed_out {
+                Err("Timeout del comando remoto".to_string())
+            } else {
+                Err(format!("Exit code {:?}", result.exit_code))
+            }
+        }
+
+        StepAction::PowerAction { server_id, action } => {
+            validate_power_action(action)?;
+            let (server, servers) = find_server(app, server_id).await?;
+            log(format!("⚡ {} su {}", action, server.name));
+
+            match action.as_str() {
+                "wake" => {
+                    let mac = server.mac_address.clone().filter(|m| !m.trim().is_empty())
+                        .ok_or_else(|| format!("{} non ha un MAC address per Wake-on-LAN", server.name))?;
+                    let result = wake_server(mac, None).await?;
+                    log(result.message.clone());
+                    if result.success { Ok(()) } else { Err(result.details.unwrap_or(result.message)) }
+                }
+                "shutdown" => {
+                    let result = shutdown_server(
+                        app.clone(),
+                        server.ip.clone(),
+                        server.ssh_user.clone(),
+                        server.ssh_port,
+                        server.password.clone(),
+                        server.shutdown_command.clone(),
+                        Some(server.id.clone()),
+                    )
+                    .await?;
+                    log(result.message.clone());
+                    if result.success { Ok(()) } else { Err(result.details.unwrap_or(result.message)) }
+                }
+                _ => {
+                    let options = ExecOptions {
+                        timeout_secs: Some(20),
+                        sudo: Some(server.ssh_user != "root"),
+                        ..Default::default()
+                    };
+                    let result = execute_on_server(app, server, servers, "shutdown -r now".to_string(), options).await?;
+                    // La connessione cade durante il riavvio: va considerato un successo
+                    if result.success() || result.connection_lost || result.timed_out {
+                        log("🔄 Riavvio avviato".to_string());
+                        Ok(())
+                    } else {
+                        Err(format!("Riavvio fallito: {}", result.stderr.trim()))
+                    }
+                }
+            }
+        }
+
+        StepAction::WaitForPort { timeout_secs, interval_secs, .. } => {
+            let (host, port) = wait_target(app, action).await?;
+            log(format!("⏳ Attesa di {}:{} (max {}s)", host, port, timeout_secs));
+
+            let deadline = Instant::now() + Duration::from_secs(*timeout_secs);
+            let address = format!("{}:{}", host, port);
+            loop {
+                if cancelled.load(Ordering::Relaxed) {
+                    return Err("Runbook annullato".to_string());
+                }
+                let attempt = tokio::time::timeout(Duration::from_secs(5), tokio::net::TcpStream::connect(&address)).await;
+                if let Ok(Ok(_)) = attempt {
+                    log(format!("✅ {} raggiungibile", address));
+                    return Ok(());
+                }
+                if Instant::now() >= deadline {
+                    return Err(format!("{} non raggiungibile entro {}s", address, timeout_secs));
+                }
+                tokio::time::sleep(Duration::from_secs((*interval_secs).max(1))).await;
+            }
+        }
+
+        StepAction::LocalCommand { command, timeout_secs } => {
+            log(format!("💻 {}", command));
+            let mut process = if cfg!(target_os = "windows") {
+                let mut c = tokio::process::Command::new("cmd");
+                c.args(["/C", command]);
+                c
+            } else {
+                let mut c = tokio::process::Command::new("sh");
+                c.args(["-c", command]);
+                c
+            };
+            process.kill_on_drop(true);
+
+            let timeout = Duration::from_secs(timeout_secs.unwrap_or(300));
+            let output = tokio::time::timeout(timeout, process.output())
+                .await
+                .map_err(|_| "Timeout del comando locale".to_string())?
+                .map_err(|e| format!("Errore avvio comando locale: {}", e))?;
+
+            for line in String::from_utf8_lossy(&output.stdout).lines().chain(String::from_utf8_lossy(&output.stderr).lines()) {
+                log(line.to_string());
+            }
+            if output.status.success() {
+                Ok(())
+            } else {
+                Err(format!("Exit code {:?}", output.status.code()))
+            }
+        }
+
+        StepAction::ManualConfirmation { message } => {
+            let (sender, receiver) = oneshot::channel();
+            {
+                let mut runs = runs().lock().unwrap();
+                let control = runs.get_mut(run_id).ok_or("Esecuzione non registrata")?;
+                control.confirmation = Some(sender);
+            }
+
+            log(format!("✋ In attesa di conferma: {}", message));
+            let _ = app.emit("runbook_confirmation_required", serde_json::json!({ "runId": run_id, "message": message }));
+
+            match receiver.await {
+                Ok(true) => {
+                    log("👍 Confermato".to_string());
+                    Ok(())
+                }
+                _ => Err("Conferma negata".to_string()),
+            }
+        }
+    }
+}
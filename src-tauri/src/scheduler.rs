@@ -0,0 +1,636 @@
+// src-tauri/src/scheduler.rs
+// Job pianificati con espressioni cron (5 campi: minuto ora giorno mese giorno-settimana)
+// Definizioni in schedules.json, storico esecuzioni in schedule-history.json
+// Se l'app era chiusa all'orario previsto si applica la policy del job ("skip" o "run_once")
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::Duration;
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Emitter};
+
+use crate::power_management::{shutdown_server, wake_server};
+use crate::remote_exec::{execute_on_server, ExecOptions};
+use crate::runbooks::run_runbook;
+use crate::snippets::execute_snippet;
+use crate::snapshots::snapshot_before_change;
+use crate::ssh_session::find_server;
+use crate::{new_id, read_json_file, write_json_file};
+
+const SCHEDULES_FILE: &str = "schedules.json";
+const HISTORY_FILE: &str = "schedule-history.json";
+const HISTORY_LIMIT: usize = 500;
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+// Oltre questo ritardo l'esecuzione è considerata persa (app chiusa o sospesa)
+const MISSED_GRACE_SECS: i64 = 120;
+
+// Serializza le modifiche a schedules.json tra comandi e ciclo dello scheduler
+static SCHEDULES_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+static RUNNING_JOBS: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+static SCHEDULER_STARTED: OnceCell<()> = OnceCell::new();
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_missed_policy() -> String {
+    "skip".to_string()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobAction {
+    #[serde(rename_all = "camelCase")]
+    Wake { server_id: String },
+    #[serde(rename_all = "camelCase")]
+    Shutdown { server_id: String },
+    #[serde(rename_all = "camelCase")]
+    RemoteCommand {
+        server_id: String,
+        command: String,
+        #[serde(default)]
+        sudo: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    Snippet {
+        server_id: String,
+        snippet_id: String,
+        #[serde(default)]
+        params: HashMap<String, String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Runbook { runbook_id: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledJob {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub cron: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub action: JobAction,
+    #[serde(default = "default_missed_policy")]
+    pub missed_run_policy: String,       // "skip" | "run_once"
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub last_run_at: Option<String>,
+    #[serde(default)]
+    pub next_run_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JobRun {
+    pub job_id: String,
+    pub job_name: String,
+    pub scheduled_for: Option<String>,
+    pub started_at: String,
+    pub finished_at: String,
+    pub success: bool,
+    pub missed: bool,
+    pub manual: bool,
+    pub message: String,
+}
+
+// ---------------------------------------------------------------------------
+// Espressioni cron
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days: Vec<bool>,
+    months: Vec<bool>,
+    weekdays: Vec<bool>,
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+const MONTH_NAMES: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+impl CronSchedule {
+    // ✅ "30 7 * * 1-5", "0 20 * * mon-fri", "*/15 * * * *", "@daily"...
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expanded = match expression.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("Espressione cron non valida (servono 5 campi): {}", expression));
+        }
+
+        let mut weekdays = parse_cron_field(fields[4], 0, 7, &WEEKDAY_NAMES)?;
+        // 7 = domenica come 0
+        if weekdays[7] {
+            weekdays[0] = true;
+        }
+        weekdays.truncate(7);
+
+        Ok(Self {
+            minutes: parse_cron_field(fields[0], 0, 59, &[])?,
+            hours: parse_cron_field(fields[1], 0, 23, &[])?,
+            days: parse_cron_field(fields[2], 1, 31, &[])?,
+            months: parse_cron_field(fields[3], 1, 12, &MONTH_NAMES)?,
+            weekdays,
+            days_restricted: fields[2] != "*",
+            weekdays_restricted: fields[4] != "*",
+        })
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let dom = self.days[date.day() as usize];
+        let dow = self.weekdays[date.weekday().num_days_from_sunday() as usize];
+        // Come in cron: se entrambi i campi sono ristretti basta che uno corrisponda
+        if self.days_restricted && self.weekdays_restricted {
+            dom || dow
+        } else {
+            dom && dow
+        }
+    }
+
+    // ✅ Primo istante (al minuto) strettamente successivo a `after`
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)? + chrono::Duration::minutes(1);
+        let mut t: NaiveDateTime = start;
+
+        for _ in 0..500_000 {
+            if !self.months[t.month() as usize] {
+                let (year, month) = if t.month() == 12 { (t.year() + 1, 1) } else { (t.year(), t.month() + 1) };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.day_matches(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.hours[t.hour() as usize] {
+                t = t.with_minute(0)? + chrono::Duration::hours(1);
+                continue;
+            }
+            if !self.minutes[t.minute() as usize] {
+                t += chrono::Duration::minutes(1);
+                continue;
+            }
+
+            // Orari inesistenti per il cambio dell'ora legale: si passa al minuto successivo
+            match Local.from_local_datetime(&t).earliest() {
+                Some(found) => return Some(found),
+                None => t += chrono::Duration::minutes(1),
+            }
+        }
+
+        None
+    }
+}
+
+fn parse_cron_value(value: &str, min: u32, names: &[&str]) -> Result<u32, String> {
+    if let Ok(number) = value.parse::<u32>() {
+        return Ok(number);
+    }
+    names
+        .iter()
+        .position(|n| n.eq_ignore_ascii_case(value))
+        .map(|i| i as u32 + min)
+        .ok_or_else(|| format!("Valore cron non valido: {}", value))
+}
+
+fn parse_cron_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<Vec<bool>, String> {
+    let mut allowed = vec![false; max as usize + 1];
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("Passo cron non valido: {}", part))?;
+                if step == 0 {
+                    return Err(format!("Passo cron non valido: {}", part));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (parse_cron_value(a, min, names)?, parse_cron_value(b, min, names)?)
+        } else {
+            let value = parse_cron_value(range, min, names)?;
+            // "5/10" = da 5 fino al massimo ogni 10
+            (value, if part.contains('/') { max } else { value })
+        };
+
+        if start < min || end > max || start > end {
+            return Err(format!("Intervallo cron fuori range ({}-{}): {}", min, max, part));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            allowed[value as usize] = true;
+        }
+    }
+
+    Ok(allowed)
+}
+
+// ---------------------------------------------------------------------------
+// Persistenza
+// ---------------------------------------------------------------------------
+
+fn parse_time(value: &str) -> Option<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Local))
+}
+
+fn with_next_run(mut job: ScheduledJob) -> ScheduledJob {
+    job.next_run_at = CronSchedule::parse(&job.cron)
+        .ok()
+        .filter(|_| job.enabled)
+        .and_then(|cron| cron.next_after(Local::now()))
+        .map(|t| t.to_rfc3339());
+    job
+}
+
+fn load_jobs(app: &AppHandle) -> Result<Vec<ScheduledJob>, String> {
+    read_json_file(app, SCHEDULES_FILE)
+}
+
+// Lettura / sostituzione completa dei job (backup e ripristino)
+pub fn load_scheduled_jobs(app: &AppHandle) -> Result<Vec<ScheduledJob>, String> {
+    let _guard = SCHEDULES_LOCK.lock().unwrap();
+    load_jobs(app)
+}
+
+pub fn replace_scheduled_jobs(app: &AppHandle, jobs: &[ScheduledJob]) -> Result<(), String> {
+    let _guard = SCHEDULES_LOCK.lock().unwrap();
+    write_json_file(app, SCHEDULES_FILE, &jobs)
+}
+
+fn append_history(app: &AppHandle, run: JobRun) -> Result<(), String> {
+    let _guard = SCHEDULES_LOCK.lock().unwrap();
+    let mut history: Vec<JobRun> = read_json_file(app, HISTORY_FILE)?;
+    history.push(run);
+    if history.len() > HISTORY_LIMIT {
+        let excess = history.len() - HISTORY_LIMIT;
+        history.drain(..excess);
+    }
+    write_json_file(app, HISTORY_FILE, &history)
+}
+
+// ✅ COMANDO: Elenco job con prossima esecuzione calcolata
+#[command]
+pub async fn list_scheduled_jobs(app: AppHandle) -> Result<Vec<ScheduledJob>, String> {
+    Ok(load_jobs(&app)?.into_iter().map(with_next_run).collect())
+}
+
+// ✅ COMANDO: Crea o aggiorna un job (id vuoto = nuovo)
+#[command]
+pub async fn save_scheduled_job(app: AppHandle, mut job: ScheduledJob) -> Result<ScheduledJob, String> {
+    if job.name.trim().is_empty() {
+        return Err("Il nome del job è obbligatorio".to_string());
+    }
+    CronSchedule::parse(&job.cron)?;
+    if job.missed_run_policy != "skip" && job.missed_run_policy != "run_once" {
+        return Err(format!("Policy non supportata: {}", job.missed_run_policy));
+    }
+
+    let _guard = SCHEDULES_LOCK.lock().unwrap();
+    let mut jobs = load_jobs(&app)?;
+
+    match jobs.iter_mut().find(|j| !job.id.is_empty() && j.id == job.id) {
+        Some(existing) => {
+            job.created_at = existing.created_at.clone();
+            job.last_run_at = existing.last_run_at.clone();
+            *existing = job.clone();
+        }
+        None => {
+            if job.id.is_empty() {
+                job.id = new_id("job");
+            }
+            job.created_at = Local::now().to_rfc3339();
+            job.last_run_at = None;
+            jobs.push(job.clone());
+        }
+    }
+
+    snapshot_before_change(&app, "salvataggio job pianificato")?;
+    write_json_file(&app, SCHEDULES_FILE, &jobs)?;
+    Ok(with_next_run(job))
+}
+
+// ✅ COMANDO: Elimina un job
+#[command]
+pub async fn delete_scheduled_job(app: AppHandle, job_id: String) -> Result<(), String> {
+    let _guard = SCHEDULES_LOCK.lock().unwrap();
+    let mut jobs = load_jobs(&app)?;
+    let before = jobs.len();
+    jobs.retain(|j| j.id != job_id);
+    if jobs.len() == before {
+        return Err(format!("Job '{}' non trovato", job_id));
+    }
+    snapshot_before_change(&app, "eliminazione job pianificato")?;
+    write_json_file(&app, SCHEDULES_FILE, &jobs)
+}
+
+// ✅ COMANDO: Abilita / disabilita un job
+// Riabilitando si riparte da adesso, senza recuperare le esecuzioni del periodo disabilitato
+#[command]
+pub async fn set_scheduled_job_enabled(app: AppHandle, job_id: String, enabled: bool) -> Result<ScheduledJob, String> {
+    let _guard = SCHEDULES_LOCK.lock().unwrap();
+    let mut jobs = load_jobs(&app)?;
+    let job = jobs
+        .iter_mut()
+        .find(|j| j.id == job_id)
+        .ok_or_else(|| format!("Job '{}' non trovato", job_id))?;
+
+    if enabled && !job.enabled {
+        job.last_run_at = Some(Local::now().to_rfc3339());
+    }
+    job.enabled = enabled;
+    let job = job.clone();
+
+    snapshot_before_change(&app, "attivazione/disattivazione job")?;
+    write_json_file(&app, SCHEDULES_FILE, &jobs)?;
+    Ok(with_next_run(job))
+}
+
+// ✅ COMANDO: Esegue subito un job (non sposta la pianificazione)
+#[command]
+pub async fn run_scheduled_job_now(app: AppHandle, job_id: String) -> Result<JobRun, String> {
+    let job = load_jobs(&app)?
+        .into_iter()
+        .find(|j| j.id == job_id)
+        .ok_or_else(|| format!("Job '{}' non trovato", job_id))?;
+    execute_job(&app, &job, None, true).await
+}
+
+// ✅ COMANDO: Storico esecuzioni (più recenti prima)
+#[command]
+pub async fn get_scheduled_job_history(
+    app: AppHandle,
+    job_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<JobRun>, String> {
+    let history: Vec<JobRun> = read_json_file(&app, HISTORY_FILE)?;
+    Ok(history
+        .into_iter()
+        .rev()
+        .filter(|r| job_id.as_ref().map_or(true, |id| &r.job_id == id))
+        .take(limit.unwrap_or(100))
+        .collect())
+}
+
+// ✅ COMANDO: Anteprima delle prossime esecuzioni di un'espressione cron
+#[command]
+pub fn preview_cron(expression: String, count: Option<usize>) -> Result<Vec<String>, String> {
+    let cron = CronSchedule::parse(&expression)?;
+    let mut times = Vec::new();
+    let mut cursor = Local::now();
+
+    for _ in 0..count.unwrap_or(5).min(50) {
+        match cron.next_after(cursor) {
+            Some(next) => {
+                times.push(next.to_rfc3339());
+                cursor = next;
+            }
+            None => break,
+        }
+    }
+    Ok(times)
+}
+
+// ---------------------------------------------------------------------------
+// Ciclo dello scheduler
+// ---------------------------------------------------------------------------
+
+// ✅ Avviato una sola volta al setup dell'app
+pub fn start_scheduler(app: AppHandle) {
+    if SCHEDULER_STARTED.set(()).is_err() {
+        return;
+    }
+
+    println!("⏰ Scheduler avviato");
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(TICK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = scheduler_tick(&app) {
+                println!("⚠️ Errore scheduler: {}", e);
+            }
+        }
+    });
+}
+
+// Individua i job scaduti, aggiorna last_run_at e li avvia in background
+fn scheduler_tick(app: &AppHandle) -> Result<(), String> {
+    let now = Local::now();
+    let mut due: Vec<(ScheduledJob, DateTime<Local>, bool)> = Vec::new();
+
+    {
+        let _guard = SCHEDULES_LOCK.lock().unwrap();
+        let mut jobs = load_jobs(app)?;
+        let mut changed = false;
+
+        for job in jobs.iter_mut().filter(|j| j.enabled) {
+            let Ok(cron) = CronSchedule::parse(&job.cron) else { continue };
+            let reference = job
+                .last_run_at
+                .as_deref()
+                .or(Some(job.created_at.as_str()))
+                .and_then(parse_time)
+                .unwrap_or(now);
+
+            let Some(next) = cron.next_after(reference) else { continue };
+            if next > now {
+                continue;
+            }
+
+            let missed = (now - next).num_seconds() > MISSED_GRACE_SECS;
+            job.last_run_at = Some(now.to_rfc3339());
+            changed = true;
+            due.push((job.clone(), next, missed));
+        }
+
+        if changed {
+            write_json_file(app, SCHEDULES_FILE, &jobs)?;
+        }
+    }
+
+    for (job, scheduled_for, missed) in due {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if missed && job.missed_run_policy != "run_once" {
+                println!("⏭️ Job '{}' saltato (previsto alle {})", job.name, scheduled_for.format("%Y-%m-%d %H:%M"));
+                let now = Local::now().to_rfc3339();
+                let run = JobRun {
+                    job_id: job.id.clone(),
+                    job_name: job.name.clone(),
+                    scheduled_for: Some(scheduled_for.to_rfc3339()),
+                    started_at: now.clone(),
+                    finished_at: now,
+                    success: false,
+                    missed: true,
+                    manual: false,
+                    message: "Esecuzione persa mentre l'app era chiusa (policy: skip)".to_string(),
+                };
+                let _ = append_history(&app, run.clone());
+                let _ = app.emit("scheduled_job_run", run);
+                return;
+            }
+
+            let _ = execute_job(&app, &job, Some(scheduled_for), false).await;
+        });
+    }
+
+    Ok(())
+}
+
+async fn execute_job(
+    app: &AppHandle,
+    job: &ScheduledJob,
+    scheduled_for: Option<DateTime<Local>>,
+    manual: bool,
+) -> Result<JobRun, String> {
+    if !RUNNING_JOBS.lock().unwrap().insert(job.id.clone()) {
+        return Err(format!("Il job '{}' è già in esecuzione", job.name));
+    }
+
+    println!("⏰ Esecuzione job '{}'", job.name);
+    let started_at = Local::now().to_rfc3339();
+    let outcome = run_job_action(app, &job.action).await;
+    RUNNING_JOBS.lock().unwrap().remove(&job.id);
+
+    let (success, message) = match outcome {
+        Ok(message) => (true, message),
+        Err(e) => (false, e),
+    };
+    let run = JobRun {
+        job_id: job.id.clone(),
+        job_name: job.name.clone(),
+        scheduled_for: scheduled_for.map(|t| t.to_rfc3339()),
+        started_at,
+        finished_at: Local::now().to_rfc3339(),
+        success,
+        missed: false,
+        manual,
+        message,
+    };
+
+    append_history(app, run.clone())?;
+    let _ = app.emit("scheduled_job_run", run.clone());
+    Ok(run)
+}
+
+async fn run_job_action(app: &AppHandle, action: &JobAction) -> Result<String, String> {
+    match action {
+        JobAction::Wake { server_id } => {
+            let (server, _) = find_server(app, server_id).await?;
+            let mac = server.mac_address.clone().filter(|m| !m.trim().is_empty())
+                .ok_or_else(|| format!("{} non ha un MAC address per Wake-on-LAN", server.name))?;
+            let result = wake_server(mac, None).await?;
+            if result.success { Ok(result.message) } else { Err(result.details.unwrap_or(result.message)) }
+        }
+        JobAction::Shutdown { server_id } => {
+            let (server, _) = find_server(app, server_id).await?;
+            let result = shutdown_server(
+                app.clone(),
+                server.ip.clone(),
+                server.ssh_user.clone(),
+                server.ssh_port,
+                server.password.clone(),
+                server.shutdown_command.clone(),
+                Some(server.id.clone()),
+            )
+            .await?;
+            if result.success { Ok(result.message) } else { Err(result.details.unwrap_or(result.message)) }
+        }
+        JobAction::RemoteCommand { server_id, command, sudo } => {
+            let (server, servers) = find_server(app, server_id).await?;
+            let options = ExecOptions { sudo: Some(*sudo), ..Default::default() };
+            let result = execute_on_server(app, server, servers, command.clone(), options).await?;
+            if result.success() {
+                Ok(format!("Completato in {} ms", result.duration_ms))
+            } else {
+                Err(format!("Exit code {:?}: {}", result.exit_code, result.stderr.trim()))
+            }
+        }
+        JobAction::Snippet { server_id, snippet_id, params } => {
+            let result = execute_snippet(app.clone(), server_id.clone(), snippet_id.clone(), Some(params.clone()), None).await?;
+            if result.success() {
+                Ok(format!("Snippet completato in {} ms", result.duration_ms))
+            } else {
+                Err(format!("Exit code {:?}: {}", result.exit_code, result.stderr.trim()))
+            }
+        }
+        JobAction::Runbook { runbook_id } => {
+            let result = run_runbook(app.clone(), runbook_id.clone(), Some(false), None).await?;
+            if result.success {
+                Ok(format!("Runbook completato ({} step)", result.steps.len()))
+            } else {
+                Err(format!("Runbook fallito allo step {:?}", result.stopped_at))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(year, month, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_ranges_steps_and_names() {
+        let cron = CronSchedule::parse("*/15 7-9 * jan,jul mon-fri").unwrap();
+        assert!(cron.minutes[0] && cron.minutes[45] && !cron.minutes[20]);
+        assert!(cron.hours[7] && cron.hours[9] && !cron.hours[10]);
+        assert!(cron.months[1] && cron.months[7] && !cron.months[2]);
+        assert!(cron.weekdays[1] && cron.weekdays[5] && !cron.weekdays[0] && !cron.weekdays[6]);
+        assert!(!cron.days_restricted && cron.weekdays_restricted);
+    }
+
+    #[test]
+    fn seven_is_sunday_and_macros_expand() {
+        let cron = CronSchedule::parse("0 0 * * 7").unwrap();
+        assert_eq!(cron.weekdays.len(), 7);
+        assert!(cron.weekdays[0]);
+
+        let daily = CronSchedule::parse("@daily").unwrap();
+        assert!(daily.minutes[0] && !daily.minutes[1] && daily.hours[0] && !daily.hours[1]);
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        assert!(CronSchedule::parse("* * * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("0 0 * * funday").is_err());
+        assert!(CronSchedule::parse("").is_err());
+    }
+
+    #[test]
+    fn next_after_skips_to_the_next_weekday() {
+        // 2024-01-12 è un venerdì: dopo le 7:30 il prossimo giorno feriale è lunedì 15
+        let cron = CronSchedule::parse("30 7 * * 1-5").unwrap();
+        assert_eq!(cron.next_after(at(2024, 1, 12, 7, 29)), Some(at(2024, 1, 12, 7, 30)));
+        assert_eq!(cron.next_after(at(2024, 1, 12, 7, 30)), Some(at(2024, 1, 15, 7, 30)));
+    }
+
+    #[test]
+    fn day_of_month_or_weekday_when_both_restricted() {
+        // Il 1° del mese oppure di domenica (2024-02-04 è una domenica)
+        let cron = CronSchedule::parse("0 12 1 * 0").unwrap();
+        assert_eq!(cron.next_after(at(2024, 2, 1, 12, 0)), Some(at(2024, 2, 4, 12, 0)));
+    }
+}
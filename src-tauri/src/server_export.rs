@@ -0,0 +1,340 @@
+// src-tauri/src/server_export.rs
+// Esportazione dei server verso altri strumenti: ssh_config, inventario Ansible (INI/YAML), CSV, JSON
+// Con strip_secrets (default) password e chiavi private in chiaro non vengono esportate
+
+use std::collections::BTreeMap;
+use std::fs;
+use tauri::{command, AppHandle};
+
+use crate::groups::{filter_by_selector, group_path, load_groups, ServerGroup};
+use crate::ssh_session::{proxy_jump_arg, resolve_jump_chain};
+use crate::{load_servers, Server};
+
+const FORMATS: [&str; 5] = ["ssh_config", "ansible_ini", "ansible_yaml", "csv", "json"];
+
+// Nome host utilizzabile come alias ssh / inventory_hostname
+fn host_alias(server: &Server) -> String {
+    let alias: String = server
+        .name
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || "-_.".contains(c) { c } else { '-' })
+        .collect();
+    if alias.is_empty() { server.ip.clone() } else { alias }
+}
+
+// Alias univoci (nomi duplicati ricevono un suffisso)
+fn unique_aliases(servers: &[Server]) -> BTreeMap<String, String> {
+    let mut aliases = BTreeMap::new();
+    let mut used: Vec<String> = Vec::new();
+
+    for server in servers {
+        let base = host_alias(server);
+        let mut alias = base.clone();
+        let mut n = 2;
+        while used.contains(&alias) {
+            alias = format!("{}-{}", base, n);
+            n += 1;
+        }
+        used.push(alias.clone());
+        aliases.insert(server.id.clone(), alias);
+    }
+    aliases
+}
+
+// Nome di gruppo valido per Ansible
+fn ansible_group(prefix: &str, name: &str) -> String {
+    let clean: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("{}_{}", prefix, clean)
+}
+
+fn ansible_vars(server: &Server, all: &[Server], strip_secrets: bool) -> Vec<(String, String)> {
+    let mut vars = vec![
+        ("ansible_host".to_string(), server.ip.clone()),
+        ("ansible_port".to_string(), server.ssh_port.to_string()),
+        ("ansible_user".to_string(), server.ssh_user.clone()),
+    ];
+    if let Some(key) = server.ssh_key_path.as_deref().filter(|k| !k.is_empty()) {
+        vars.push(("ansible_ssh_private_key_file".to_string(), key.to_string()));
+    }
+    if !strip_secrets && server.auth_method == "password" {
+        if let Some(password) = &server.password {
+            vars.push(("ansible_password".to_string(), password.clone()));
+        }
+    }
+    if let Some(jump) = resolve_jump_chain(server, all).ok().and_then(|chain| proxy_jump_arg(&chain)) {
+        vars.push(("ansible_ssh_common_args".to_string(), format!("-o ProxyJump={}", jump)));
+    }
+    vars
+}
+
+// Nomi Ansible dei gruppi DevPulse dal percorso completo (prod/web -> group_prod_web):
+// sottogruppi omonimi restano distinti; in caso di collisione residua si aggiunge l'id
+fn ansible_group_names(groups: &[ServerGroup]) -> BTreeMap<String, String> {
+    let mut names = BTreeMap::new();
+    let mut used: Vec<String> = Vec::new();
+
+    for group in groups {
+        let mut name = ansible_group("group", &group_path(group, groups));
+        if used.contains(&name) {
+            name = ansible_group("group", &format!("{} {}", group_path(group, groups), group.id));
+        }
+        used.push(name.clone());
+        names.insert(group.id.clone(), name);
+    }
+    names
+}
+
+// Gruppi Ansible: tipo server, tag e gruppi DevPulse (con i sottogruppi come children)
+fn ansible_groups(servers: &[Server], groups: &[ServerGroup]) -> BTreeMap<String, (Vec<String>, Vec<String>)> {
+    let mut result: BTreeMap<String, (Vec<String>, Vec<String>)> = BTreeMap::new();
+
+    for server in servers {
+        if !server.server_type.trim().is_empty() {
+            result.entry(ansible_group("type", &server.server_type)).or_default().0.push(server.id.clone());
+        }
+        for tag in &server.tags {
+            result.entry(ansible_group("tag", tag)).or_default().0.push(server.id.clone());
+        }
+    }
+
+    let group_names = ansible_group_names(groups);
+    for group in groups {
+        let entry = result.entry(group_names[&group.id].clone()).or_default();
+        entry.0.extend(group.server_ids.iter().filter(|id| servers.iter().any(|s| &s.id == *id)).cloned());
+        for child in groups.iter().filter(|g| g.parent_id.as_deref() == Some(group.id.as_str())) {
+            entry.1.push(group_names[&child.id].clone());
+        }
+    }
+
+    result.retain(|_, (hosts, children)| !hosts.is_empty() || !children.is_empty());
+    result
+}
+
+fn render_ssh_config(servers: &[Server], all: &[Server]) -> String {
+    let aliases = unique_aliases(all);
+    let mut out = format!("# Generato da DevPulse il {}\n", chrono::Local::now().format("%Y-%m-%d %H:%M"));
+
+    for server in servers {
+        out.push_str(&format!("\nHost {}\n", aliases[&server.id]));
+        out.push_str(&format!("    HostName {}\n", server.ip));
+        out.push_str(&format!("    User {}\n", server.ssh_user));
+        out.push_str(&format!("    Port {}\n", server.ssh_port));
+        if let Some(key) = server.ssh_key_path.as_deref().filter(|k| !k.is_empty()) {
+            out.push_str(&format!("    IdentityFile \"{}\"\n", key));
+        }
+        // ProxyJump verso l'alias del jump host se esportato anche lui (OpenSSH risolve da solo il resto
+        // della catena), altrimenti la catena esplicita user@host:port come per Ansible
+        let exported_jump = server
+            .jump_host
+            .as_ref()
+            .filter(|id| servers.iter().any(|s| &s.id == *id))
+            .and_then(|id| aliases.get(id))
+            .cloned();
+        let jump = exported_jump.or_else(|| resolve_jump_chain(server, all).ok().and_then(|chain| proxy_jump_arg(&chain)));
+        if let Some(jump) = jump {
+            out.push_str(&format!("    ProxyJump {}\n", jump));
+        }
+    }
+    out
+}
+
+fn render_ansible_ini(servers: &[Server], all: &[Server], groups: &[ServerGroup], strip_secrets: bool) -> String {
+    let aliases = unique_aliases(all);
+    let mut out = String::from("[all]\n");
+
+    for server in servers {
+        let vars: Vec<String> = ansible_vars(server, all, strip_secrets)
+            .into_iter()
+            .map(|(k, v)| if v.contains(' ') { format!("{}='{}'", k, v) } else { format!("{}={}", k, v) })
+            .collect();
+        out.push_str(&format!("{} {}\n", aliases[&server.id], vars.join(" ")));
+    }
+
+    for (group, (hosts, children)) in ansible_groups(servers, groups) {
+        if !hosts.is_empty() {
+            out.push_str(&format!("\n[{}]\n", group));
+            for id in hosts {
+                out.push_str(&format!("{}\n", aliases[&id]));
+            }
+        }
+        if !children.is_empty() {
+            out.push_str(&format!("\n[{}:children]\n", group));
+            for child in children {
+                out.push_str(&format!("{}\n", child));
+            }
+        }
+    }
+    out
+}
+
+fn render_ansible_yaml(servers: &[Server], all: &[Server], groups: &[ServerGroup], strip_secrets: bool) -> Result<String, String> {
+    let aliases = unique_aliases(all);
+
+    let hosts: serde_json::Map<String, serde_json::Value> = servers
+        .iter()
+        .map(|server| {
+            let vars: serde_json::Map<String, serde_json::Value> = ansible_vars(server, all, strip_secrets)
+                .into_iter()
+                .map(|(k, v)| {
+                    let value = if k == "ansible_port" { serde_json::json!(server.ssh_port) } else { serde_json::json!(v) };
+                    (k, value)
+                })
+                .collect();
+            (aliases[&server.id].clone(), serde_json::Value::Object(vars))
+        })
+        .collect();
+
+    let children: serde_json::Map<String, serde_json::Value> = ansible_groups(servers, groups)
+        .into_iter()
+        .map(|(group, (members, subgroups))| {
+            let mut body = serde_json::Map::new();
+            if !members.is_empty() {
+                let members: serde_json::Map<String, serde_json::Value> = members
+                    .iter()
+                    .map(|id| (aliases[id].clone(), serde_json::Value::Null))
+                    .collect();
+                body.insert("hosts".to_string(), serde_json::Value::Object(members));
+            }
+            if !subgroups.is_empty() {
+                let subgroups: serde_json::Map<String, serde_json::Value> = subgroups
+                    .into_iter()
+                    .map(|g| (g, serde_json::Value::Null))
+                    .collect();
+                body.insert("children".to_string(), serde_json::Value::Object(subgroups));
+            }
+            (group, serde_json::Value::Object(body))
+        })
+        .collect();
+
+    let inventory = serde_json::json!({ "all": { "hosts": hosts, "children": children } });
+    serde_yaml::to_string(&inventory).map_err(|e| format!("Errore generazione YAML: {}", e))
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn render_csv(servers: &[Server], strip_secrets: bool) -> String {
+    let mut header = vec![
+        "id", "name", "ip", "ssh_port", "ssh_user", "auth_method", "ssh_key_path",
+        "server_type", "mac_address", "wol_enabled", "jump_host", "tags",
+    ];
+    if !strip_secrets {
+        header.push("password");
+    }
+
+    let mut out = header.join(",") + "\n";
+    for s in servers {
+        let mut row = vec![
+            s.id.clone(),
+            s.name.clone(),
+            s.ip.clone(),
+            s.ssh_port.to_string(),
+            s.ssh_user.clone(),
+            s.auth_method.clone(),
+            s.ssh_key_path.clone().unwrap_or_default(),
+            s.server_type.clone(),
+            s.mac_address.clone().unwrap_or_default(),
+            s.wol_enabled.unwrap_or(false).to_string(),
+            s.jump_host.clone().unwrap_or_default(),
+            s.tags.join(";"),
+        ];
+        if !strip_secrets {
+            row.push(s.password.clone().unwrap_or_default());
+        }
+        out.push_str(&row.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        out.push('\n');
+    }
+    out
+}
+
+// ✅ Rimuove password e chiavi private in chiaro
+pub fn strip_server_secrets(server: &Server) -> Server {
+    let mut clean = server.clone();
+    clean.password = None;
+    // Il frontend salva a volte il percorso della chiave in ssh_key: quello non è un segreto
+    if clean.ssh_key.contains("PRIVATE KEY") {
+        clean.ssh_key = String::new();
+    }
+    clean
+}
+
+// ✅ COMANDO: Genera l'export nel formato richiesto (anteprima o salvataggio)
+#[command]
+pub async fn render_server_export(
+    app: AppHandle,
+    format: String,
+    strip_secrets: Option<bool>,
+    selector: Option<String>,
+) -> Result<String, String> {
+    if !FORMATS.contains(&format.as_str()) {
+        return Err(format!("Formato non supportato: {} (disponibili: {})", format, FORMATS.join(", ")));
+    }
+
+    let strip_secrets = strip_secrets.unwrap_or(true);
+    let all = load_servers(app.clone()).await?;
+    let groups = load_groups(&app)?;
+    let servers = match selector.as_deref().filter(|s| !s.trim().is_empty()) {
+        Some(selector) => filter_by_selector(&all, &groups, selector)?,
+        None => all.clone(),
+    };
+    let servers: Vec<Server> = if strip_secrets { servers.iter().map(strip_server_secrets).collect() } else { servers };
+
+    match format.as_str() {
+        "ssh_config" => Ok(render_ssh_config(&servers, &all)),
+        "ansible_ini" => Ok(render_ansible_ini(&servers, &all, &groups, strip_secrets)),
+        "ansible_yaml" => render_ansible_yaml(&servers, &all, &groups, strip_secrets),
+        "csv" => Ok(render_csv(&servers, strip_secrets)),
+        _ => serde_json::to_string_pretty(&servers).map_err(|e| e.to_string()),
+    }
+}
+
+// ✅ COMANDO: Esporta su file (dialog di salvataggio se il percorso non è indicato)
+#[command]
+pub async fn export_servers(
+    app: AppHandle,
+    format: String,
+    strip_secrets: Option<bool>,
+    selector: Option<String>,
+    path: Option<String>,
+) -> Result<String, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let content = render_server_export(app.clone(), format.clone(), strip_secrets, selector).await?;
+
+    let (default_name, extension) = match format.as_str() {
+        "ssh_config" => ("devpulse-ssh-config".to_string(), "conf"),
+        "ansible_ini" => ("devpulse-inventory.ini".to_string(), "ini"),
+        "ansible_yaml" => ("devpulse-inventory.yml".to_string(), "yml"),
+        "csv" => (format!("devpulse-servers-{}.csv", chrono::Local::now().format("%Y-%m-%d")), "csv"),
+        _ => (format!("devpulse-servers-{}.json", chrono::Local::now().format("%Y-%m-%d")), "json"),
+    };
+
+    let path = match path {
+        Some(path) => std::path::PathBuf::from(path),
+        None => {
+            let file_path = app
+                .dialog()
+                .file()
+                .set_file_name(&default_name)
+                .add_filter(&format, &[extension])
+                .blocking_save_file()
+                .ok_or("Esportazione annullata")?;
+            file_path.as_path().ok_or("Percorso file non valido")?.to_path_buf()
+        }
+    };
+
+    fs::write(&path, content).map_err(|e| format!("Errore scrittura {}: {}", path.display(), e))?;
+    println!("📤 Export {} salvato in {}", format, path.display());
+    Ok(path.to_string_lossy().to_string())
+}
@@ -0,0 +1,434 @@
+// src-tauri/src/server_import.rs
+// Flusso comune di importazione: anteprima (diff per server) e merge in servers.json
+// Modalità: replace, append_new (solo nuovi), upsert_id, upsert_host (ip + porta)
+// Usato dall'import JSON di DevPulse e dagli importer di formati esterni (ssh_config, ...)
+// I record con errori di validazione vengono scartati e riportati in `issues`, gli altri importati
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use serde::Serialize;
+use tauri::{command, AppHandle};
+
+use crate::snapshots::snapshot_before_change;
+use crate::validation::{parse_server_records, validate_server, RecordValidation};
+use crate::{load_servers, new_id, store_servers, Server};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MergeMode {
+    Replace,                             // la lista importata sostituisce quella locale
+    AppendNew,                           // aggiunge solo i server non presenti, gli altri restano invariati
+    UpsertId,                            // aggiorna per id, aggiunge gli altri
+    UpsertHost,                          // aggiorna per ip + porta, aggiunge gli altri
+}
+
+impl MergeMode {
+    pub fn parse(value: Option<&str>, default: MergeMode) -> Result<Self, String> {
+        match value.map(|v| v.trim()) {
+            None | Some("") => Ok(default),
+            Some("replace") => Ok(MergeMode::Replace),
+            Some("append_new") => Ok(MergeMode::AppendNew),
+            Some("upsert_id") => Ok(MergeMode::UpsertId),
+            Some("upsert_host") => Ok(MergeMode::UpsertHost),
+            Some(other) => Err(format!(
+                "Modalità di import non supportata: {} (replace, append_new, upsert_id, upsert_host)",
+                other
+            )),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            MergeMode::Replace => "replace",
+            MergeMode::AppendNew => "append_new",
+            MergeMode::UpsertId => "upsert_id",
+            MergeMode::UpsertHost => "upsert_host",
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportChange {
+    pub action: String,                  // "create" | "update" | "unchanged" | "remove"
+    pub server: Server,                  // record risultante (o quello rimosso)
+    pub existing_id: Option<String>,
+    pub changed_fields: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    pub source: String,
+    pub mode: String,
+    pub changes: Vec<ImportChange>,
+    pub warnings: Vec<String>,
+    pub issues: Vec<RecordValidation>,   // errori / warning per record (indice nel file sorgente)
+    pub rejected: usize,                 // record scartati perché non validi
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+    pub applied: bool,
+}
+
+fn same_host(a: &Server, b: &Server) -> bool {
+    a.ip.trim().eq_ignore_ascii_case(b.ip.trim()) && a.ssh_port == b.ssh_port
+}
+
+fn find_match<'a>(existing: &'a [Server], incoming: &Server, mode: MergeMode) -> Option<&'a Server> {
+    let by_id = || existing.iter().find(|s| !incoming.id.is_empty() && s.id == incoming.id);
+    let by_host = || existing.iter().find(|s| same_host(s, incoming));
+
+    match mode {
+        MergeMode::Replace | MergeMode::UpsertId => by_id(),
+        MergeMode::UpsertHost => by_host(),
+        MergeMode::AppendNew => by_id().or_else(by_host),
+    }
+}
+
+// Aggiorna solo i dati di connessione; stato, energia, tag, tunnel ecc. restano quelli salvati
+// (per i formati esterni che non conoscono il resto dei campi)
+fn merge_connection(existing: &Server, incoming: &Server) -> Server {
+    let mut merged = existing.clone();
+    merged.ip = incoming.ip.clone();
+    merged.ssh_user = incoming.ssh_user.clone();
+    merged.ssh_port = incoming.ssh_port;
+    if incoming.ssh_key_path.is_some() {
+        merged.ssh_key_path = incoming.ssh_key_path.clone();
+    }
+    if incoming.jump_host.is_some() {
+        merged.jump_host = incoming.jump_host.clone();
+    }
+    // Chi si autentica con password la mantiene: i file importati di solito non la contengono
+    if existing.auth_method != "password" {
+        merged.auth_method = incoming.auth_method.clone();
+    }
+    merged
+}
+
+// Campi (nomi camelCase come nel JSON) che differiscono tra due record
+fn diff_fields(before: &Server, after: &Server) -> Vec<String> {
+    let (Ok(serde_json::Value::Object(a)), Ok(serde_json::Value::Object(b))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return Vec::new();
+    };
+
+    b.iter()
+        .filter(|(key, value)| a.get(*key) != Some(*value))
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+// Valida i record in arrivo: ritorna quelli importabili e aggiunge a `issues` errori e warning.
+// `issues` contiene già i record scartati in lettura, quindi l'indice nel sorgente salta le loro posizioni
+fn validate_incoming(incoming: Vec<Server>, issues: &mut Vec<RecordValidation>) -> Vec<Server> {
+    let unreadable: Vec<usize> = issues.iter().map(|i| i.index).collect();
+    let mut source_index = (0..).filter(|i| !unreadable.contains(i));
+    let mut valid = Vec::new();
+
+    for server in incoming {
+        let mut validation = validate_server(&server);
+        validation.index = source_index.next().unwrap_or_default();
+
+        let accepted = validation.is_valid();
+        if !validation.errors.is_empty() || !validation.warnings.is_empty() {
+            issues.push(validation);
+        }
+        if accepted {
+            valid.push(server);
+        }
+    }
+
+    issues.sort_by_key(|i| i.index);
+    valid
+}
+
+// ✅ Costruisce l'anteprima del merge
+// full_records=true: i record importati sono completi (JSON DevPulse) e sostituiscono quelli locali;
+// false: si aggiornano solo i dati di connessione
+pub fn build_preview(
+    source: &str,
+    existing: &[Server],
+    incoming: Vec<Server>,
+    warnings: Vec<String>,
+    issues: Vec<RecordValidation>,
+    mode: MergeMode,
+    full_records: bool,
+) -> ImportPreview {
+    let mut warnings = warnings;
+    let mut issues = issues;
+    let incoming = validate_incoming(incoming, &mut issues);
+    let mut matched_ids: Vec<String> = Vec::new();
+    let mut changes: Vec<ImportChange> = Vec::new();
+    // Id nel file -> id finale, per riscrivere i jump_host che puntano a record rinumerati
+    let mut id_map: HashMap<String, String> = HashMap::new();
+
+    // Con "replace" un file con soli record scartati svuoterebbe la lista locale
+    let nothing_valid = incoming.is_empty() && issues.iter().any(|i| !i.is_valid());
+    if mode == MergeMode::Replace && nothing_valid {
+        warnings.push("Nessun record valido: i server locali non verranno rimossi".to_string());
+    }
+
+    for mut server in incoming {
+        let source_id = server.id.clone();
+        match find_match(existing, &server, mode) {
+            Some(current) if matched_ids.contains(&current.id) => {
+                warnings.push(format!("{}: corrisponde a un server già importato nello stesso file, ignorato", server.name));
+            }
+            Some(current) => {
+                matched_ids.push(current.id.clone());
+                if !source_id.is_empty() {
+                    id_map.insert(source_id, current.id.clone());
+                }
+
+                let merged = if mode == MergeMode::AppendNew {
+                    current.clone()
+                } else if full_records {
+                    server.id = current.id.clone();
+                    server
+                } else {
+                    merge_connection(current, &server)
+                };
+                let changed_fields = diff_fields(current, &merged);
+
+                changes.push(ImportChange {
+                    action: if changed_fields.is_empty() { "unchanged" } else { "update" }.to_string(),
+                    server: merged,
+                    existing_id: Some(current.id.clone()),
+                    changed_fields,
+                });
+            }
+            None => {
+                // Id vuoto o già usato da un altro server locale: ne serve uno nuovo
+                let taken = existing.iter().any(|s| s.id == server.id) || changes.iter().any(|c| c.server.id == server.id);
+                if server.id.is_empty() || taken {
+                    server.id = new_id("server");
+                    if !source_id.is_empty() {
+                        id_map.insert(source_id, server.id.clone());
+                    }
+                }
+                changes.push(ImportChange {
+                    action: "create".to_string(),
+                    server,
+                    existing_id: None,
+                    changed_fields: Vec::new(),
+                });
+            }
+        }
+    }
+
+    if mode == MergeMode::Replace && !nothing_valid {
+        for server in existing.iter().filter(|s| !matched_ids.contains(&s.id)) {
+            changes.push(ImportChange {
+                action: "remove".to_string(),
+                server: server.clone(),
+                existing_id: Some(server.id.clone()),
+                changed_fields: Vec::new(),
+            });
+        }
+    }
+
+    // jump_host negli import esterni è un riferimento per nome o host: lo si converte nell'id finale
+    // (i server rimossi dal "replace" non sono più validi come destinazione)
+    let removed: Vec<String> = changes
+        .iter()
+        .filter(|c| c.action == "remove")
+        .map(|c| c.server.id.clone())
+        .collect();
+    let known: Vec<(String, String, String)> = changes
+        .iter()
+        .filter(|c| c.action != "remove")
+        .map(|c| &c.server)
+        .chain(existing.iter().filter(|s| !removed.contains(&s.id)))
+        .map(|s| (s.id.clone(), s.name.to_lowercase(), s.ip.to_lowercase()))
+        .collect();
+
+    for change in changes.iter_mut().filter(|c| c.action != "remove") {
+        let Some(reference) = change.server.jump_host.clone() else { continue };
+        if let Some(id) = id_map.get(&reference) {
+            change.server.jump_host = Some(id.clone());
+            continue;
+        }
+        if known.iter().any(|(id, _, _)| id == &reference) {
+            continue;
+        }
+
+        let lower = reference.to_lowercase();
+        match known.iter().find(|(_, name, ip)| name == &lower || ip == &lower) {
+            Some((id, _, _)) => change.server.jump_host = Some(id.clone()),
+            None => {
+                warnings.push(format!("{}: jump host '{}' non trovato, ignorato", change.server.name, reference));
+                change.server.jump_host = None;
+            }
+        }
+    }
+
+    let count = |action: &str| changes.iter().filter(|c| c.action == action).count();
+    ImportPreview {
+        rejected: issues.iter().filter(|i| !i.is_valid()).count(),
+        source: source.to_string(),
+        mode: mode.name().to_string(),
+        created: count("create"),
+        updated: count("update"),
+        unchanged: count("unchanged"),
+        removed: count("remove"),
+        changes,
+        warnings,
+        issues,
+        applied: false,
+    }
+}
+
+// ✅ Applica l'anteprima a servers.json (con restore point dello stato precedente)
+pub fn apply_preview(app: &AppHandle, existing: Vec<Server>, mut preview: ImportPreview) -> Result<ImportPreview, String> {
+    snapshot_before_change(app, &format!("import da {}", preview.source))?;
+
+    let mut servers = existing;
+    for change in &preview.changes {
+        let existing_id = change.existing_id.as_ref();
+        match change.action.as_str() {
+            "create" => servers.push(change.server.clone()),
+            "update" => {
+                if let Some(slot) = servers.iter_mut().find(|s| Some(&s.id) == existing_id) {
+                    *slot = change.server.clone();
+                }
+            }
+            "remove" => servers.retain(|s| Some(&s.id) != existing_id),
+            _ => {}
+        }
+    }
+
+    store_servers(app, &servers)?;
+    println!(
+        "📥 Import da {} ({}): {} creati, {} aggiornati, {} rimossi, {} scartati",
+        preview.source, preview.mode, preview.created, preview.updated, preview.removed, preview.rejected
+    );
+    preview.applied = true;
+    Ok(preview)
+}
+
+// File JSON da importare: percorso indicato o scelto con il dialog
+pub fn pick_import_file(app: &AppHandle, path: Option<String>, filter_name: &str, extensions: &[&str]) -> Result<PathBuf, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    if let Some(path) = path.filter(|p| !p.trim().is_empty()) {
+        return Ok(PathBuf::from(path));
+    }
+
+    let file_path = app
+        .dialog()
+        .file()
+        .add_filter(filter_name, extensions)
+        .blocking_pick_file()
+        .ok_or("Nessun file selezionato")?;
+    Ok(file_path.as_path().ok_or("Percorso file non valido")?.to_path_buf())
+}
+
+async fn prepare_json_import(app: &AppHandle, path: Option<String>, mode: Option<String>) -> Result<(Vec<Server>, ImportPreview), String> {
+    let mode = MergeMode::parse(mode.as_deref(), MergeMode::UpsertId)?;
+    let path = pick_import_file(app, path, "JSON files", &["json"])?;
+
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let (incoming, issues) = parse_server_records(&content)?;
+
+    let existing = load_servers(app.clone()).await?;
+    let preview = build_preview(&path.to_string_lossy(), &existing, incoming, Vec::new(), issues, mode, true);
+    Ok((existing, preview))
+}
+
+// ✅ COMANDO: Dry-run dell'import di un file DevPulse (diff per server, nulla viene salvato)
+#[command]
+pub async fn preview_servers_import(app: AppHandle, path: Option<String>, mode: Option<String>) -> Result<ImportPreview, String> {
+    Ok(prepare_json_import(&app, path, mode).await?.1)
+}
+
+// ✅ COMANDO: Import di un file DevPulse con la modalità di merge scelta
+#[command]
+pub async fn import_servers_with_mode(app: AppHandle, path: Option<String>, mode: Option<String>) -> Result<ImportPreview, String> {
+    let (existing, preview) = prepare_json_import(&app, path, mode).await?;
+    apply_preview(&app, existing, preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(id: &str, name: &str, ip: &str) -> Server {
+        Server {
+            id: id.to_string(),
+            name: name.to_string(),
+            ip: ip.to_string(),
+            ssh_port: 22,
+            ssh_user: "root".to_string(),
+            auth_method: "password".to_string(),
+            password: Some("secret".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn actions(preview: &ImportPreview) -> Vec<(&str, &str)> {
+        preview.changes.iter().map(|c| (c.action.as_str(), c.server.name.as_str())).collect()
+    }
+
+    #[test]
+    fn upsert_host_updates_connection_and_creates_the_rest() {
+        let existing = vec![server("s1", "web", "10.0.0.1")];
+        let mut renamed = server("", "web-imported", "10.0.0.1");
+        renamed.ssh_user = "deploy".to_string();
+        let incoming = vec![renamed, server("", "db", "10.0.0.2")];
+
+        let preview = build_preview("test", &existing, incoming, Vec::new(), Vec::new(), MergeMode::UpsertHost, false);
+
+        assert_eq!(actions(&preview), [("update", "web"), ("create", "db")]);
+        assert_eq!(preview.changes[0].existing_id.as_deref(), Some("s1"));
+        assert_eq!(preview.changes[0].changed_fields, ["sshUser"]);
+        assert!(preview.changes[1].server.id.starts_with("server-"));
+        assert_eq!((preview.created, preview.updated, preview.removed), (1, 1, 0));
+    }
+
+    #[test]
+    fn replace_removes_unmatched_and_append_new_keeps_existing() {
+        let existing = vec![server("s1", "web", "10.0.0.1"), server("s2", "old", "10.0.0.3")];
+        let incoming = vec![server("s1", "web", "10.0.0.1")];
+
+        let replace = build_preview("test", &existing, incoming.clone(), Vec::new(), Vec::new(), MergeMode::Replace, true);
+        assert_eq!(actions(&replace), [("unchanged", "web"), ("remove", "old")]);
+
+        let mut changed = incoming[0].clone();
+        changed.ssh_port = 2222;
+        let append = build_preview("test", &existing, vec![changed], Vec::new(), Vec::new(), MergeMode::AppendNew, true);
+        assert_eq!(actions(&append), [("unchanged", "web")]);
+        assert_eq!(append.changes[0].server.ssh_port, 22);
+    }
+
+    #[test]
+    fn invalid_records_are_rejected_without_emptying_the_list() {
+        let existing = vec![server("s1", "web", "10.0.0.1")];
+        let incoming = vec![server("s9", "", "not a host")];
+
+        let preview = build_preview("test", &existing, incoming, Vec::new(), Vec::new(), MergeMode::Replace, true);
+
+        assert!(preview.changes.is_empty());
+        assert_eq!(preview.rejected, 1);
+        assert_eq!(preview.issues[0].index, 0);
+        assert!(preview.warnings.iter().any(|w| w.contains("Nessun record valido")));
+    }
+
+    #[test]
+    fn jump_host_references_are_resolved_to_final_ids() {
+        let mut app = server("", "app", "10.0.0.10");
+        app.jump_host = Some("bastion".to_string());
+        let mut orphan = server("", "orphan", "10.0.0.11");
+        orphan.jump_host = Some("missing".to_string());
+        let incoming = vec![server("", "bastion", "10.0.0.9"), app, orphan];
+
+        let preview = build_preview("test", &[], incoming, Vec::new(), Vec::new(), MergeMode::UpsertHost, false);
+
+        let bastion_id = preview.changes[0].server.id.clone();
+        assert_eq!(preview.changes[1].server.jump_host, Some(bastion_id));
+        assert_eq!(preview.changes[2].server.jump_host, None);
+        assert!(preview.warnings.iter().any(|w| w.contains("'missing'")));
+    }
+}
@@ -0,0 +1,602 @@
+// src-tauri/src/session_import.rs
+// Importazione delle sessioni da altri client SSH: PuTTY (.reg), Termius (JSON/CSV),
+// Remmina (.remmina, file singolo o cartella) e MobaXterm (.mxtsessions)
+// I record passano per lo stesso flusso anteprima + merge dell'import JSON e di ssh_config
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde_json::Value;
+use tauri::{command, AppHandle};
+
+use crate::server_import::{apply_preview, build_preview, pick_import_file, ImportPreview, MergeMode};
+use crate::{load_servers, Server};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SessionFormat {
+    Putty,
+    Termius,
+    Remmina,
+    MobaXterm,
+}
+
+impl SessionFormat {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "putty" => Ok(SessionFormat::Putty),
+            "termius" => Ok(SessionFormat::Termius),
+            "remmina" => Ok(SessionFormat::Remmina),
+            "mobaxterm" => Ok(SessionFormat::MobaXterm),
+            other => Err(format!("Formato non supportato: {} (putty, termius, remmina, mobaxterm)", other)),
+        }
+    }
+
+    fn file_filter(&self) -> (&'static str, &'static [&'static str]) {
+        match self {
+            SessionFormat::Putty => ("PuTTY registry export", &["reg"]),
+            SessionFormat::Termius => ("Termius export", &["json", "csv"]),
+            SessionFormat::Remmina => ("Remmina", &["remmina"]),
+            SessionFormat::MobaXterm => ("MobaXterm sessions", &["mxtsessions"]),
+        }
+    }
+}
+
+// Dati di una sessione prima della conversione in Server
+#[derive(Default)]
+struct SessionEntry {
+    name: String,
+    host: String,
+    port: Option<u16>,
+    user: Option<String>,
+    password: Option<String>,
+    key_path: Option<String>,   // percorso o, negli export Termius, contenuto PEM della chiave
+    tags: Vec<String>,
+}
+
+fn local_user() -> String {
+    std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_else(|_| "root".to_string())
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+// "user@host" -> (Some(user), host)
+fn split_user_host(value: &str) -> (Option<String>, String) {
+    match value.rsplit_once('@') {
+        Some((user, host)) => (non_empty(user), host.trim().to_string()),
+        None => (None, value.trim().to_string()),
+    }
+}
+
+// Percorso di un file di chiave (non un'etichetta come "My key" né il contenuto della chiave)
+fn is_path_like(value: &str) -> bool {
+    value.contains('/') || value.contains('\\') || value.starts_with('~')
+}
+
+fn into_servers(entries: Vec<SessionEntry>, warnings: &mut Vec<String>) -> Vec<Server> {
+    let default_user = local_user();
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            let name = non_empty(&entry.name).unwrap_or_else(|| entry.host.clone());
+            let ssh_user = entry.user.unwrap_or_else(|| {
+                warnings.push(format!("{}: utente non indicato, uso '{}'", name, default_user));
+                default_user.clone()
+            });
+            let (key_path, ssh_key) = match entry.key_path {
+                Some(key) if key.contains("PRIVATE KEY") => {
+                    warnings.push(format!("{}: chiave privata inclusa nell'export, salvata nel server", name));
+                    (None, key)
+                }
+                Some(key) if is_path_like(&key) => (Some(key), String::new()),
+                Some(key) => {
+                    warnings.push(format!("{}: chiave '{}' non è un percorso, ignorata", name, key));
+                    (None, String::new())
+                }
+                None => (None, String::new()),
+            };
+            if key_path.as_deref().map_or(false, |k| k.to_lowercase().ends_with(".ppk")) {
+                warnings.push(format!("{}: chiave PuTTY (.ppk), va convertita in formato OpenSSH con puttygen", name));
+            }
+            let has_key = key_path.is_some() || !ssh_key.is_empty();
+
+            Server {
+                id: String::new(),
+                name,
+                ip: entry.host,
+                ssh_user,
+                ssh_port: entry.port.unwrap_or(22),
+                auth_method: if entry.password.is_some() && !has_key { "password" } else { "key" }.to_string(),
+                password: entry.password,
+                ssh_key_path: key_path,
+                ssh_key,
+                server_type: "Custom".to_string(),
+                status: "offline".to_string(),
+                tags: entry.tags,
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+// Testo con BOM UTF-8 / UTF-16 (regedit esporta in UTF-16LE)
+fn read_text(path: &Path) -> Result<String, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Impossibile leggere {}: {}", path.display(), e))?;
+
+    let utf16 = |data: &[u8], little_endian: bool| {
+        let units: Vec<u16> = data
+            .chunks_exact(2)
+            .map(|c| if little_endian { u16::from_le_bytes([c[0], c[1]]) } else { u16::from_be_bytes([c[0], c[1]]) })
+            .collect();
+        String::from_utf16_lossy(&units)
+    };
+
+    Ok(match bytes.as_slice() {
+        [0xFF, 0xFE, rest @ ..] => utf16(rest, true),
+        [0xFE, 0xFF, rest @ ..] => utf16(rest, false),
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8_lossy(rest).to_string(),
+        _ => String::from_utf8_lossy(&bytes).to_string(),
+    })
+}
+
+// INI minimale: sezioni -> coppie chiave/valore nell'ordine del file
+fn parse_ini(content: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut sections: Vec<(String, Vec<(String, String)>)> = vec![(String::new(), Vec::new())];
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((section.to_string(), Vec::new()));
+        } else if let Some((key, value)) = line.split_once('=') {
+            sections.last_mut().unwrap().1.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    sections
+}
+
+// %XX -> carattere (nomi delle sessioni PuTTY)
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(byte) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+// ---------------------------------------------------------------------------
+// PuTTY: export del registro HKCU\Software\SimonTatham\PuTTY\Sessions
+// ---------------------------------------------------------------------------
+
+// "Nome"="valore con \\ e \"" oppure "Nome"=dword:00000016
+fn parse_reg_value(line: &str) -> Option<(String, String)> {
+    let rest = line.strip_prefix('"')?;
+    let (key, value) = rest.split_once("\"=")?;
+
+    if let Some(hex) = value.strip_prefix("dword:") {
+        return Some((key.to_string(), u32::from_str_radix(hex.trim(), 16).ok()?.to_string()));
+    }
+    let quoted = value.strip_prefix('"')?.strip_suffix('"')?;
+    Some((key.to_string(), quoted.replace("\\\\", "\u{0}").replace("\\\"", "\"").replace('\u{0}', "\\")))
+}
+
+// Come parse_ini ma con i valori del registro (chiavi tra virgolette, escape, dword)
+fn parse_ini_reg(content: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut sections: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((section.to_string(), Vec::new()));
+        } else if let (Some(last), Some(value)) = (sections.last_mut(), parse_reg_value(line)) {
+            last.1.push(value);
+        }
+    }
+    sections
+}
+
+fn parse_putty(content: &str, warnings: &mut Vec<String>) -> Vec<SessionEntry> {
+    const SESSIONS_KEY: &str = "\\software\\simontatham\\putty\\sessions\\";
+    let mut entries = Vec::new();
+
+    for (section, values) in parse_ini_reg(content) {
+        let lower = section.to_lowercase();
+        let Some(pos) = lower.find(SESSIONS_KEY) else { continue };
+        let name = percent_decode(&section[pos + SESSIONS_KEY.len()..]);
+        if name == "Default Settings" {
+            continue;
+        }
+
+        let values: HashMap<String, String> = values.into_iter().collect();
+        let protocol = values.get("Protocol").map(|p| p.as_str()).unwrap_or("ssh");
+        if protocol != "ssh" {
+            warnings.push(format!("{}: protocollo {} ignorato", name, protocol));
+            continue;
+        }
+        let Some(host) = values.get("HostName").and_then(|h| non_empty(h)) else {
+            warnings.push(format!("{}: HostName mancante, ignorata", name));
+            continue;
+        };
+
+        let (host_user, host) = split_user_host(&host);
+        entries.push(SessionEntry {
+            name,
+            host,
+            port: values.get("PortNumber").and_then(|p| p.parse().ok()),
+            user: values.get("UserName").and_then(|u| non_empty(u)).or(host_user),
+            key_path: values.get("PublicKeyFile").and_then(|k| non_empty(k)),
+            ..Default::default()
+        });
+    }
+
+    entries
+}
+
+// ---------------------------------------------------------------------------
+// Termius: export JSON (piatto o con ssh_config / identity annidati) o CSV
+// ---------------------------------------------------------------------------
+
+// Primo campo presente tra i nomi indicati, cercando anche negli oggetti annidati
+fn json_field(object: &Value, names: &[&str]) -> Option<String> {
+    let map = object.as_object()?;
+    for (key, value) in map {
+        let key = key.to_lowercase();
+        if names.contains(&key.as_str()) {
+            match value {
+                Value::String(s) if !s.trim().is_empty() => return Some(s.trim().to_string()),
+                Value::Number(n) => return Some(n.to_string()),
+                Value::Object(_) => {
+                    // es. "ssh_key": { "label": ..., "path": ... }
+                    if let Some(inner) = json_field(value, &["path", "file"]) {
+                        return Some(inner);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    map.values().filter(|v| v.is_object()).find_map(|v| json_field(v, names))
+}
+
+fn json_tags(object: &Value) -> Vec<String> {
+    let mut tags = Vec::new();
+    let Some(map) = object.as_object() else { return tags };
+    for (key, value) in map {
+        match (key.to_lowercase().as_str(), value) {
+            ("tags", Value::Array(items)) => tags.extend(items.iter().filter_map(|t| match t {
+                Value::String(s) => non_empty(s),
+                other => json_field(other, &["label", "name"]),
+            })),
+            ("tags", Value::String(s)) => tags.extend(s.split(',').filter_map(non_empty)),
+            ("group", Value::String(s)) => tags.extend(non_empty(s)),
+            ("group", other @ Value::Object(_)) => tags.extend(json_field(other, &["label", "name"])),
+            _ => {}
+        }
+    }
+    tags
+}
+
+const NAME_FIELDS: &[&str] = &["label", "name", "alias"];
+const HOST_FIELDS: &[&str] = &["address", "hostname", "host", "hostname/ip", "ip"];
+const PORT_FIELDS: &[&str] = &["port"];
+const USER_FIELDS: &[&str] = &["username", "user", "login"];
+const PASSWORD_FIELDS: &[&str] = &["password"];
+const KEY_FIELDS: &[&str] = &["ssh_key", "ssh key", "key", "identity_file", "private_key", "key_path"];
+
+fn parse_termius_json(content: &str, warnings: &mut Vec<String>) -> Result<Vec<SessionEntry>, String> {
+    let root: Value = serde_json::from_str(content).map_err(|e| format!("JSON Termius non valido: {}", e))?;
+    let hosts = match &root {
+        Value::Array(items) => items.clone(),
+        Value::Object(map) => map
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case("hosts"))
+            .and_then(|(_, v)| v.as_array().cloned())
+            .ok_or("Nessun elenco 'hosts' nel file Termius")?,
+        _ => return Err("Formato Termius non riconosciuto".to_string()),
+    };
+
+    let mut entries = Vec::new();
+    for (index, host) in hosts.iter().enumerate() {
+        let Some(address) = json_field(host, HOST_FIELDS) else {
+            warnings.push(format!("Host #{}: indirizzo mancante, ignorato", index + 1));
+            continue;
+        };
+        let (host_user, address) = split_user_host(&address);
+        entries.push(SessionEntry {
+            name: json_field(host, NAME_FIELDS).unwrap_or_default(),
+            host: address,
+            port: json_field(host, PORT_FIELDS).and_then(|p| p.parse().ok()),
+            user: json_field(host, USER_FIELDS).or(host_user),
+            password: json_field(host, PASSWORD_FIELDS),
+            key_path: json_field(host, KEY_FIELDS),
+            tags: json_tags(host),
+        });
+    }
+    Ok(entries)
+}
+
+// CSV con virgolette ("" = virgoletta) e a capo nei campi
+fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => in_quotes = !in_quotes,
+            (',', false) => row.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (c, _) => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows.retain(|r| r.iter().any(|f| !f.trim().is_empty()));
+    rows
+}
+
+fn parse_termius_csv(content: &str, warnings: &mut Vec<String>) -> Result<Vec<SessionEntry>, String> {
+    let rows = parse_csv(content);
+    let (header, records) = rows.split_first().ok_or("File CSV vuoto")?;
+    let header: Vec<String> = header.iter().map(|h| h.trim().to_lowercase()).collect();
+
+    let column = |names: &[&str]| header.iter().position(|h| names.contains(&h.as_str()));
+    let host_col = column(HOST_FIELDS).ok_or("Colonna hostname/address non trovata nel CSV")?;
+    let (name_col, port_col, user_col) = (column(NAME_FIELDS), column(PORT_FIELDS), column(USER_FIELDS));
+    let (password_col, key_col) = (column(PASSWORD_FIELDS), column(KEY_FIELDS));
+    let tag_cols: Vec<usize> = header
+        .iter()
+        .enumerate()
+        .filter(|(_, h)| ["tags", "group", "groups"].contains(&h.as_str()))
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut entries = Vec::new();
+    for (index, record) in records.iter().enumerate() {
+        let get = |col: Option<usize>| col.and_then(|c| record.get(c)).and_then(|v| non_empty(v));
+        let Some(address) = get(Some(host_col)) else {
+            warnings.push(format!("Riga {}: indirizzo mancante, ignorata", index + 2));
+            continue;
+        };
+        let (host_user, address) = split_user_host(&address);
+        entries.push(SessionEntry {
+            name: get(name_col).unwrap_or_default(),
+            host: address,
+            port: get(port_col).and_then(|p| p.parse().ok()),
+            user: get(user_col).or(host_user),
+            password: get(password_col),
+            key_path: get(key_col),
+            tags: tag_cols
+                .iter()
+                .filter_map(|c| get(Some(*c)))
+                .flat_map(|v| v.split(',').filter_map(non_empty).collect::<Vec<_>>())
+                .collect(),
+        });
+    }
+    Ok(entries)
+}
+
+// ---------------------------------------------------------------------------
+// Remmina: un file .remmina per connessione (solo protocolli SSH / SFTP)
+// ---------------------------------------------------------------------------
+
+fn parse_remmina(path: &Path, content: &str, warnings: &mut Vec<String>) -> Option<SessionEntry> {
+    let values: HashMap<String, String> = parse_ini(content)
+        .into_iter()
+        .filter(|(section, _)| section.eq_ignore_ascii_case("remmina"))
+        .flat_map(|(_, values)| values)
+        .collect();
+
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let name = values.get("name").and_then(|n| non_empty(n)).unwrap_or_else(|| file_name.clone());
+    let protocol = values.get("protocol").map(|p| p.to_uppercase()).unwrap_or_default();
+    if protocol != "SSH" && protocol != "SFTP" {
+        warnings.push(format!("{}: protocollo {} ignorato", name, if protocol.is_empty() { "?" } else { &protocol }));
+        return None;
+    }
+
+    let Some(server) = values.get("server").and_then(|s| non_empty(s)) else {
+        warnings.push(format!("{}: server mancante, ignorato", name));
+        return None;
+    };
+    // "host:porta" oppure "[ipv6]:porta"
+    let (host, port) = match server.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') || host.ends_with(']') => {
+            (host.trim_start_matches('[').trim_end_matches(']').to_string(), port.parse().ok())
+        }
+        _ => (server.clone(), None),
+    };
+    let (host_user, host) = split_user_host(&host);
+
+    let user = ["ssh_username", "username"].iter().find_map(|k| values.get(*k).and_then(|u| non_empty(u)));
+    // ssh_tunnel_privatekey è la chiave del tunnel, non quella del server
+    let key_path = values.get("ssh_privatekey").and_then(|p| non_empty(p));
+    if values.get("ssh_tunnel_enabled").map_or(false, |v| v.trim() == "1") {
+        let tunnel = values.get("ssh_tunnel_server").and_then(|t| non_empty(t)).unwrap_or_else(|| "?".to_string());
+        warnings.push(format!("{}: tunnel SSH via {} non supportato, ignorato", name, tunnel));
+    }
+    let group = values.get("group").and_then(|g| non_empty(g));
+
+    Some(SessionEntry {
+        name,
+        host,
+        port,
+        user: user.or(host_user),
+        key_path,
+        tags: group.into_iter().collect(),
+        ..Default::default()
+    })
+}
+
+fn remmina_files(path: &Path) -> Result<Vec<PathBuf>, String> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(path)
+        .map_err(|e| format!("Impossibile leggere {}: {}", path.display(), e))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().map_or(false, |ext| ext == "remmina"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+// ---------------------------------------------------------------------------
+// MobaXterm: sezioni [Bookmarks], [Bookmarks_N] con SubRep = cartella
+// Sessione SSH: "#icona#0%host%porta%utente%...%chiave%..." (tipo 0 = SSH)
+// ---------------------------------------------------------------------------
+
+fn mobaxterm_path(value: &str) -> String {
+    let home = std::env::var("USERPROFILE").unwrap_or_default();
+    value.replace("_ProfileDir_", &home).replace("_CurrentDrive_", "C")
+}
+
+fn parse_mobaxterm(content: &str, warnings: &mut Vec<String>) -> Vec<SessionEntry> {
+    let mut entries = Vec::new();
+
+    for (section, values) in parse_ini(content) {
+        if !section.to_lowercase().starts_with("bookmarks") {
+            continue;
+        }
+        let folder = values.iter().find(|(k, _)| k == "SubRep").and_then(|(_, v)| non_empty(v));
+
+        for (name, value) in values.iter().filter(|(k, _)| k != "SubRep" && k != "ImgNum") {
+            let Some(session) = value.strip_prefix('#').and_then(|v| v.split_once('#')).map(|(_, rest)| rest) else {
+                continue;
+            };
+            let fields: Vec<&str> = session.split('#').next().unwrap_or("").split('%').collect();
+            if fields.first() != Some(&"0") {
+                warnings.push(format!("{}: sessione non SSH ignorata", name));
+                continue;
+            }
+
+            let Some(host) = fields.get(1).and_then(|h| non_empty(h)) else {
+                warnings.push(format!("{}: host mancante, ignorata", name));
+                continue;
+            };
+            // La posizione della chiave cambia tra le versioni: si cerca il campo che somiglia a un file di chiave
+            let key_path = fields.iter().skip(4).find(|f| {
+                let lower = f.to_lowercase();
+                lower.ends_with(".ppk") || lower.ends_with(".pem") || lower.ends_with(".key") || lower.contains("id_rsa") || lower.contains("id_ed25519")
+            });
+
+            entries.push(SessionEntry {
+                name: name.clone(),
+                host,
+                port: fields.get(2).and_then(|p| p.parse().ok()),
+                user: fields.get(3).and_then(|u| non_empty(u)),
+                key_path: key_path.map(|k| mobaxterm_path(k)),
+                tags: folder.iter().flat_map(|f| f.split('\\').filter_map(non_empty)).collect(),
+                ..Default::default()
+            });
+        }
+    }
+
+    entries
+}
+
+// ✅ Converte un export di un altro client in record Server (non ancora salvati)
+fn parse_sessions(format: SessionFormat, path: &Path) -> Result<(Vec<Server>, Vec<String>), String> {
+    let mut warnings = Vec::new();
+
+    let entries = match format {
+        SessionFormat::Putty => parse_putty(&read_text(path)?, &mut warnings),
+        SessionFormat::Termius => {
+            let content = read_text(path)?;
+            let is_json = path.extension().map_or(false, |e| e.eq_ignore_ascii_case("json"))
+                || content.trim_start().starts_with(['{', '[']);
+            if is_json {
+                parse_termius_json(&content, &mut warnings)?
+            } else {
+                parse_termius_csv(&content, &mut warnings)?
+            }
+        }
+        SessionFormat::Remmina => {
+            let mut entries = Vec::new();
+            for file in remmina_files(path)? {
+                match read_text(&file) {
+                    Ok(content) => entries.extend(parse_remmina(&file, &content, &mut warnings)),
+                    Err(e) => warnings.push(e),
+                }
+            }
+            entries
+        }
+        SessionFormat::MobaXterm => parse_mobaxterm(&read_text(path)?, &mut warnings),
+    };
+
+    if entries.is_empty() {
+        warnings.push("Nessuna sessione SSH trovata nel file".to_string());
+    }
+    Ok((into_servers(entries, &mut warnings), warnings))
+}
+
+async fn prepare_import(
+    app: &AppHandle,
+    format: String,
+    path: Option<String>,
+    names: Option<Vec<String>>,
+    mode: Option<String>,
+) -> Result<(Vec<Server>, ImportPreview), String> {
+    let format = SessionFormat::parse(&format)?;
+    let mode = MergeMode::parse(mode.as_deref(), MergeMode::UpsertHost)?;
+    let (filter_name, extensions) = format.file_filter();
+    let path = pick_import_file(app, path, filter_name, extensions)?;
+
+    let (mut incoming, warnings) = parse_sessions(format, &path)?;
+    if let Some(names) = names {
+        incoming.retain(|s| names.contains(&s.name));
+    }
+
+    let existing = load_servers(app.clone()).await?;
+    let preview = build_preview(&path.to_string_lossy(), &existing, incoming, warnings, Vec::new(), mode, false);
+    Ok((existing, preview))
+}
+
+// ✅ COMANDO: Anteprima dell'import da PuTTY / Termius / Remmina / MobaXterm
+#[command]
+pub async fn preview_sessions_import(
+    app: AppHandle,
+    format: String,
+    path: Option<String>,
+    mode: Option<String>,
+) -> Result<ImportPreview, String> {
+    Ok(prepare_import(&app, format, path, None, mode).await?.1)
+}
+
+// ✅ COMANDO: Importa le sessioni (eventualmente solo quelle scelte nell'anteprima)
+#[command]
+pub async fn import_sessions(
+    app: AppHandle,
+    format: String,
+    path: Option<String>,
+    names: Option<Vec<String>>,
+    mode: Option<String>,
+) -> Result<ImportPreview, String> {
+    let (existing, preview) = prepare_import(&app, format, path, names, mode).await?;
+    apply_preview(&app, existing, preview)
+}
@@ -0,0 +1,290 @@
+// src-tauri/src/session_registry.rs
+// Registro delle sessioni SSH interattive aperte dal frontend (start_ssh_session / close_ssh_session)
+// Ogni sessione ha un id opaco, è mantenuta viva con keepalive e viene chiusa all'uscita dell'app
+
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use ssh2::{Channel, ErrorCode, Session};
+use tauri::{command, AppHandle, Emitter};
+
+use crate::ssh_session::{open_session, take_utf8};
+use crate::{load_servers, new_id, Server};
+
+const KEEPALIVE_INTERVAL_SECS: u32 = 30;
+const KEEPALIVE_CHECK: Duration = Duration::from_secs(10);
+const SHELL_POLL: Duration = Duration::from_millis(10);
+const LIBSSH2_ERROR_EAGAIN: i32 = -37;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SshConnectionRequest {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub auth_method: String,
+    pub password: Option<String>,
+    pub key_path: Option<String>,
+    pub server_id: Option<String>,       // se presente si usa il server salvato (con jump host)
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SshSessionInfo {
+    pub session_id: String,
+    pub server_id: Option<String>,
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub connected_at: String,
+}
+
+struct ActiveSession {
+    session: Session,
+    info: SshSessionInfo,
+    shell: Option<Sender<Vec<u8>>>,     // input della shell interattiva, aperta al primo uso
+}
+
+static SSH_SESSIONS: Lazy<Mutex<HashMap<String, ActiveSession>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static KEEPALIVE_STARTED: OnceCell<()> = OnceCell::new();
+
+// Server salvato corrispondente alla richiesta (per ereditare jump host e chiavi),
+// altrimenti un server ad-hoc con i soli dati di connessione
+fn resolve_target(connection: &SshConnectionRequest, servers: &[Server]) -> Server {
+    let saved = servers.iter().find(|s| match &connection.server_id {
+        Some(id) => &s.id == id,
+        None => s.ip == connection.host && s.ssh_port == connection.port && s.ssh_user == connection.username,
+    });
+
+    let mut server = saved.cloned().unwrap_or_else(|| Server {
+        id: String::new(),
+        name: connection.host.clone(),
+        ip: connection.host.clone(),
+        ssh_port: connection.port,
+        ssh_user: connection.username.clone(),
+        ..Default::default()
+    });
+
+    // I dati inviati dal frontend hanno la precedenza su quelli salvati
+    server.auth_method = connection.auth_method.clone();
+    if connection.password.is_some() {
+        server.password = connection.password.clone();
+    }
+    if connection.key_path.is_some() {
+        server.ssh_key_path = connection.key_path.clone();
+    }
+    server
+}
+
+// ✅ COMANDO: Apre una sessione SSH autenticata e ritorna il suo id
+#[command]
+pub async fn start_ssh_session(app: AppHandle, connection: SshConnectionRequest) -> Result<String, String> {
+    if connection.host.trim().is_empty() || connection.username.trim().is_empty() {
+        return Err("Host e utente sono obbligatori".to_string());
+    }
+
+    let servers = load_servers(app.clone()).await?;
+    let server = resolve_target(&connection, &servers);
+
+    println!("🔐 Apertura sessione SSH {}@{}:{}", server.ssh_user, server.ip, server.ssh_port);
+    let session = tokio::task::spawn_blocking(move || {
+        let session = open_session(&server, &servers)?;
+        session.set_keepalive(true, KEEPALIVE_INTERVAL_SECS);
+        Ok::<_, String>(session)
+    })
+    .await
+    .map_err(|e| format!("Errore task sessione SSH: {}", e))??;
+
+    let session_id = new_id("ssh");
+    let info = SshSessionInfo {
+        session_id: session_id.clone(),
+        server_id: connection.server_id.clone(),
+        host: connection.host.clone(),
+        port: connection.port,
+        username: connection.username.clone(),
+        connected_at: chrono::Local::now().to_rfc3339(),
+    };
+    SSH_SESSIONS.lock().unwrap().insert(session_id.clone(), ActiveSession { session, info, shell: None });
+
+    start_keepalive_loop(app);
+    println!("✅ Sessione SSH {} attiva", session_id);
+    Ok(session_id)
+}
+
+// ✅ COMANDO: Chiude una sessione SSH
+#[command]
+pub fn close_ssh_session(session_id: String) -> Result<(), String> {
+    let active = SSH_SESSIONS
+        .lock()
+        .unwrap()
+        .remove(&session_id)
+        .ok_or_else(|| format!("Sessione '{}' non trovata", session_id))?;
+
+    let _ = active.session.disconnect(None, "Sessione chiusa da DevPulse", None);
+    println!("🔌 Sessione SSH {} chiusa", session_id);
+    Ok(())
+}
+
+// ✅ COMANDO: Sessioni attualmente aperte
+#[command]
+pub fn list_ssh_sessions() -> Vec<SshSessionInfo> {
+    SSH_SESSIONS.lock().unwrap().values().map(|s| s.info.clone()).collect()
+}
+
+// ✅ Scrive nella shell interattiva della sessione (aperta al primo uso)
+// L'output della shell arriva al frontend con l'evento "ssh_session_output"
+pub fn write_to_session_shell(app: &AppHandle, session_id: &str, data: &[u8]) -> Result<(), String> {
+    let (session, shell) = {
+        let sessions = SSH_SESSIONS.lock().unwrap();
+        let active = sessions
+            .get(session_id)
+            .ok_or_else(|| format!("Sessione '{}' non trovata", session_id))?;
+        (active.session.clone(), active.shell.clone())
+    };
+
+    if let Some(shell) = shell {
+        if shell.send(data.to_vec()).is_ok() {
+            return Ok(());
+        }
+    }
+
+    // Shell mai aperta o terminata: ne apre una nuova fuori dal lock del registro
+    let shell = open_shell(app, session_id, &session)?;
+    shell
+        .send(data.to_vec())
+        .map_err(|_| "Shell della sessione chiusa".to_string())?;
+    if let Some(active) = SSH_SESSIONS.lock().unwrap().get_mut(session_id) {
+        active.shell = Some(shell);
+    }
+    Ok(())
+}
+
+fn open_shell(app: &AppHandle, session_id: &str, session: &Session) -> Result<Sender<Vec<u8>>, String> {
+    session.set_blocking(true);
+    let mut channel = session
+        .channel_session()
+        .map_err(|e| format!("Errore apertura canale shell: {}", e))?;
+    channel
+        .request_pty("xterm", None, Some((120, 32, 0, 0)))
+        .map_err(|e| format!("Errore richiesta pty: {}", e))?;
+    channel
+        .shell()
+        .map_err(|e| format!("Errore avvio shell: {}", e))?;
+
+    let (sender, receiver) = mpsc::channel();
+    let app = app.clone();
+    let session = session.clone();
+    let session_id = session_id.to_string();
+    thread::spawn(move || {
+        run_shell(&app, &session_id, &session, channel, receiver);
+        session.set_blocking(true);
+        let _ = app.emit("ssh_session_shell_closed", serde_json::json!({ "sessionId": session_id }));
+    });
+
+    Ok(sender)
+}
+
+// Pompa input/output della shell in modalità non bloccante (il keepalive usa la stessa sessione)
+fn run_shell(app: &AppHandle, session_id: &str, session: &Session, mut channel: Channel, input: Receiver<Vec<u8>>) {
+    session.set_blocking(false);
+    let mut pending_input: Vec<u8> = Vec::new();
+    let mut output: Vec<u8> = Vec::new();
+    let mut buf = [0u8; 8 * 1024];
+
+    loop {
+        let mut progressed = false;
+
+        loop {
+            match input.try_recv() {
+                Ok(data) => pending_input.extend(data),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    let _ = channel.close();
+                    return;
+                }
+            }
+        }
+
+        if !pending_input.is_empty() {
+            match channel.write(&pending_input) {
+                Ok(n) => {
+                    pending_input.drain(..n);
+                    progressed = true;
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(_) => return,
+            }
+        }
+
+        match channel.read(&mut buf) {
+            Ok(0) => {}
+            Ok(n) => {
+                output.extend_from_slice(&buf[..n]);
+                let text = take_utf8(&mut output);
+                if !text.is_empty() {
+                    let _ = app.emit("ssh_session_output", serde_json::json!({
+                        "sessionId": session_id,
+                        "data": text,
+                    }));
+                }
+                progressed = true;
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(_) => return,
+        }
+
+        if channel.eof() {
+            return;
+        }
+        if !progressed {
+            thread::sleep(SHELL_POLL);
+        }
+    }
+}
+
+// ✅ Chiusura di tutte le sessioni (uscita dall'app)
+pub fn close_all_ssh_sessions() {
+    let sessions: Vec<ActiveSession> = SSH_SESSIONS.lock().unwrap().drain().map(|(_, s)| s).collect();
+    for active in sessions {
+        let _ = active.session.disconnect(None, "DevPulse in chiusura", None);
+    }
+}
+
+// Un solo thread invia i keepalive a tutte le sessioni e rimuove quelle cadute
+fn start_keepalive_loop(app: AppHandle) {
+    if KEEPALIVE_STARTED.set(()).is_err() {
+        return;
+    }
+
+    thread::spawn(move || loop {
+        thread::sleep(KEEPALIVE_CHECK);
+
+        let sessions: Vec<(String, Session)> = SSH_SESSIONS
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, s)| (id.clone(), s.session.clone()))
+            .collect();
+
+        for (session_id, session) in sessions {
+            // Con la shell aperta la sessione è non bloccante: EAGAIN non indica una sessione persa
+            let result = session
+                .keepalive_send()
+                .map(|_| ())
+                .or_else(|e| if e.code() == ErrorCode::Session(LIBSSH2_ERROR_EAGAIN) { Ok(()) } else { Err(e) });
+            if let Err(e) = result {
+                println!("⚠️ Sessione SSH {} persa: {}", session_id, e);
+                SSH_SESSIONS.lock().unwrap().remove(&session_id);
+                let _ = app.emit("ssh_session_closed", serde_json::json!({
+                    "sessionId": session_id,
+                    "reason": e.to_string(),
+                }));
+            }
+        }
+    });
+}
@@ -0,0 +1,548 @@
+// src-tauri/src/sftp.rs
+// Browser file remoto e trasferimenti SFTP sopra ssh2
+// Progress via evento "sftp_progress", annullabile con sftp_cancel_transfer
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use ssh2::{FileStat, Sftp};
+use tauri::{command, AppHandle, Emitter};
+
+use crate::new_id;
+use crate::ssh_session::{find_server, open_session};
+
+static TRANSFERS: OnceCell<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceCell::new();
+
+const CHUNK_SIZE: usize = 32 * 1024;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteEntry {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+    pub is_symlink: bool,
+    pub size: u64,
+    pub permissions: Option<String>,  // es. "755"
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+    pub modified: Option<u64>,        // epoch secondi
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferProgress {
+    pub transfer_id: String,
+    pub direction: String,            // "upload" | "download"
+    pub current_file: String,
+    pub files_done: u32,
+    pub files_total: u32,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    pub finished: bool,
+    pub cancelled: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferResult {
+    pub transfer_id: String,
+    pub files: u32,
+    pub bytes: u64,
+    pub cancelled: bool,
+}
+
+fn entry_from_stat(path: &Path, stat: &FileStat, is_symlink: bool) -> RemoteEntry {
+    RemoteEntry {
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string_lossy().to_string()),
+        path: path.to_string_lossy().to_string(),
+        is_dir: stat.is_dir(),
+        is_symlink,
+        size: stat.size.unwrap_or(0),
+        permissions: stat.perm.map(|p| format!("{:o}", p & 0o7777)),
+        uid: stat.uid,
+        gid: stat.gid,
+        modified: stat.mtime,
+    }
+}
+
+// Apre una sessione SFTP sul server ed esegue l'operazione in un thread bloccante
+async fn with_sftp<T, F>(app: &AppHandle, server_id: &str, op: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&Sftp) -> Result<T, String> + Send + 'static,
+{
+    let (server, servers) = find_server(app, server_id).await?;
+
+    tokio::task::spawn_blocking(move || {
+        let session = open_session(&server, &servers)?;
+        let sftp = session
+            .sftp()
+            .map_err(|e| format!("Sottosistema SFTP non disponibile: {}", e))?;
+        op(&sftp)
+    })
+    .await
+    .map_err(|e| format!("Errore task SFTP: {}", e))?
+}
+
+// ✅ COMANDO: Contenuto di una directory remota
+#[command]
+pub async fn sftp_list_dir(app: AppHandle, server_id: String, path: String) -> Result<Vec<RemoteEntry>, String> {
+    with_sftp(&app, &server_id, move |sftp| {
+        let dir = if path.trim().is_empty() { "." } else { path.as_str() };
+        let dir = sftp
+            .realpath(Path::new(dir))
+            .map_err(|e| format!("Percorso {} non valido: {}", dir, e))?;
+
+        let mut entries: Vec<RemoteEntry> = sftp
+            .readdir(&dir)
+            .map_err(|e| format!("Impossibile leggere {}: {}", dir.display(), e))?
+            .iter()
+            .map(|(entry_path, stat)| {
+                let is_symlink = stat.file_type().is_symlink();
+                // Per i link simbolici mostra il tipo della destinazione
+                let target = if is_symlink { sftp.stat(entry_path).ok() } else { None };
+                entry_from_stat(entry_path, target.as_ref().unwrap_or(stat), is_symlink)
+            })
+            .collect();
+
+        entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+        Ok(entries)
+    })
+    .await
+}
+
+// ✅ COMANDO: Informazioni su un file remoto
+#[command]
+pub async fn sftp_stat(app: AppHandle, server_id: String, path: String) -> Result<RemoteEntry, String> {
+    with_sftp(&app, &server_id, move |sftp| {
+        let remote = Path::new(&path);
+        let lstat = sftp
+            .lstat(remote)
+            .map_err(|e| format!("Stat di {} fallito: {}", path, e))?;
+        let is_symlink = lstat.file_type().is_symlink();
+        let stat = if is_symlink { sftp.stat(remote).unwrap_or(lstat) } else { lstat };
+        Ok(entry_from_stat(remote, &stat, is_symlink))
+    })
+    .await
+}
+
+// ✅ COMANDO: Crea una directory remota
+#[command]
+pub async fn sftp_mkdir(app: AppHandle, server_id: String, path: String, mode: Option<String>) -> Result<(), String> {
+    let mode = parse_mode(mode.as_deref().unwrap_or("755"))?;
+    with_sftp(&app, &server_id, move |sftp| {
+        sftp.mkdir(Path::new(&path), mode as i32)
+            .map_err(|e| format!("Creazione di {} fallita: {}", path, e))
+    })
+    .await
+}
+
+// ✅ COMANDO: Rinomina / sposta un file remoto
+#[command]
+pub async fn sftp_rename(app: AppHandle, server_id: String, from: String, to: String) -> Result<(), String> {
+    with_sftp(&app, &server_id, move |sftp| {
+        sftp.rename(Path::new(&from), Path::new(&to), None)
+            .map_err(|e| format!("Rinomina {} -> {} fallita: {}", from, to, e))
+    })
+    .await
+}
+
+// ✅ COMANDO: Elimina file o directory (ricorsivo solo se richiesto)
+#[command]
+pub async fn sftp_delete(app: AppHandle, server_id: String, path: String, recursive: Option<bool>) -> Result<(), String> {
+    let recursive = recursive.unwrap_or(false);
+    with_sftp(&app, &server_id, move |sftp| {
+        let remote = Path::new(&path);
+        let stat = sftp
+            .lstat(remote)
+            .map_err(|e| format!("{} non trovato: {}", path, e))?;
+
+        if stat.is_dir() {
+            if recursive {
+                remove_dir_recursive(sftp, remote)
+            } else {
+                sftp.rmdir(remote)
+                    .map_err(|e| format!("Rimozione directory {} fallita: {}", path, e))
+            }
+        } else {
+            sftp.unlink(remote)
+                .map_err(|e| format!("Eliminazione {} fallita: {}", path, e))
+        }
+    })
+    .await
+}
+
+// ✅ COMANDO: Cambia i permessi (ottale, es. "644")
+#[command]
+pub async fn sftp_chmod(app: AppHandle, server_id: String, path: String, mode: String) -> Result<(), String> {
+    let mode = parse_mode(&mode)?;
+    with_sftp(&app, &server_id, move |sftp| {
+        let stat = FileStat {
+            size: None,
+            uid: None,
+            gid: None,
+            perm: Some(mode),
+            atime: None,
+            mtime: None,
+        };
+        sftp.setstat(Path::new(&path), stat)
+            .map_err(|e| format!("chmod {} fallito: {}", path, e))
+    })
+    .await
+}
+
+// ✅ COMANDO: Scarica un file o una directory (ricorsiva)
+#[command]
+pub async fn sftp_download(
+    app: AppHandle,
+    server_id: String,
+    remote_path: String,
+    local_path: String,
+    transfer_id: Option<String>,
+) -> Result<TransferResult, String> {
+    let transfer_id = transfer_id.unwrap_or_else(|| new_id("xfer"));
+    let cancel = register_transfer(&transfer_id);
+    let app_clone = app.clone();
+    let id = transfer_id.clone();
+
+    let result = with_sftp(&app, &server_id, move |sftp| {
+        let remote = Path::new(&remote_path);
+        let stat = sftp
+            .stat(remote)
+            .map_err(|e| format!("{} non trovato: {}", remote_path, e))?;
+
+        let mut plan = Vec::new();
+        if stat.is_dir() {
+            plan_remote_tree(sftp, remote, Path::new(&local_path), &mut plan)?;
+        } else {
+            plan.push(TransferItem::File {
+                remote: remote.to_path_buf(),
+                local: PathBuf::from(&local_path),
+                size: stat.size.unwrap_or(0),
+            });
+        }
+
+        let mut tracker = ProgressTracker::new(&app_clone, &id, "download", &plan);
+        for item in &plan {
+            match item {
+                TransferItem::Dir { local, .. } => {
+                    fs::create_dir_all(local).map_err(|e| format!("Errore creazione {}: {}", local.display(), e))?;
+                }
+                TransferItem::File { remote, local, .. } => {
+                    if let Some(parent) = local.parent() {
+                        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                    }
+                    let mut source = sftp
+                        .open(remote)
+                        .map_err(|e| format!("Apertura {} fallita: {}", remote.display(), e))?;
+                    let mut target = fs::File::create(local)
+                        .map_err(|e| format!("Creazione {} fallita: {}", local.display(), e))?;
+                    if !copy_stream(&mut source, &mut target, &remote.to_string_lossy(), &cancel, &mut tracker)? {
+                        drop(target);
+                        let _ = fs::remove_file(local);
+                        return Ok(tracker.finish(true));
+                    }
+                }
+            }
+        }
+
+        Ok(tracker.finish(false))
+    })
+    .await;
+
+    finish_transfer(&app, &transfer_id, "download", result)
+}
+
+// ✅ COMANDO: Carica un file o una directory (ricorsiva)
+#[command]
+pub async fn sftp_upload(
+    app: AppHandle,
+    server_id: String,
+    local_path: String,
+    remote_path: String,
+    transfer_id: Option<String>,
+) -> Result<TransferResult, String> {
+    let transfer_id = transfer_id.unwrap_or_else(|| new_id("xfer"));
+    let cancel = register_transfer(&transfer_id);
+    let app_clone = app.clone();
+    let id = transfer_id.clone();
+
+    let result = with_sftp(&app, &server_id, move |sftp| {
+        let local = Path::new(&local_path);
+        let metadata = fs::metadata(local)
+            .map_err(|e| format!("{} non trovato: {}", local_path, e))?;
+
+        let mut plan = Vec::new();
+        if metadata.is_dir() {
+            plan_local_tree(local, Path::new(&remote_path), &mut plan)?;
+        } else {
+            plan.push(TransferItem::File {
+                remote: PathBuf::from(&remote_path),
+                local: local.to_path_buf(),
+                size: metadata.len(),
+            });
+        }
+
+        let mut tracker = ProgressTracker::new(&app_clone, &id, "upload", &plan);
+        for item in &plan {
+            match item {
+                TransferItem::Dir { remote, .. } => {
+                    // La directory può già esistere: errore solo se non è una directory
+                    if sftp.mkdir(remote, 0o755).is_err() && !sftp.stat(remote).map(|s| s.is_dir()).unwrap_or(false) {
+                        return Err(format!("Creazione directory remota {} fallita", remote.display()));
+                    }
+                }
+                TransferItem::File { remote, local, .. } => {
+                    let mut source = fs::File::open(local)
+                        .map_err(|e| format!("Apertura {} fallita: {}", local.display(), e))?;
+                    let mut target = sftp
+                        .create(remote)
+                        .map_err(|e| format!("Creazione {} fallita: {}", remote.display(), e))?;
+                    if !copy_stream(&mut source, &mut target, &local.to_string_lossy(), &cancel, &mut tracker)? {
+                        drop(target);
+                        let _ = sftp.unlink(remote);
+                        return Ok(tracker.finish(true));
+                    }
+                }
+            }
+        }
+
+        Ok(tracker.finish(false))
+    })
+    .await;
+
+    finish_transfer(&app, &transfer_id, "upload", result)
+}
+
+// ✅ COMANDO: Annulla un trasferimento in corso
+#[command]
+pub fn sftp_cancel_transfer(transfer_id: String) -> Result<(), String> {
+    let transfers = TRANSFERS.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap();
+    match transfers.get(&transfer_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            println!("⏹️ Trasferimento {} annullato", transfer_id);
+            Ok(())
+        }
+        None => Err(format!("Trasferimento '{}' non attivo", transfer_id)),
+    }
+}
+
+fn register_transfer(transfer_id: &str) -> Arc<AtomicBool> {
+    let flag = Arc::new(AtomicBool::new(false));
+    TRANSFERS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap()
+        .insert(transfer_id.to_string(), flag.clone());
+    flag
+}
+
+fn finish_transfer(
+    app: &AppHandle,
+    transfer_id: &str,
+    direction: &str,
+    result: Result<TransferResult, String>,
+) -> Result<TransferResult, String> {
+    if let Some(lock) = TRANSFERS.get() {
+        lock.lock().unwrap().remove(transfer_id);
+    }
+
+    // Gli errori arrivano anche come evento, così il pannello trasferimenti si aggiorna
+    if let Err(e) = &result {
+        let _ = app.emit("sftp_progress", TransferProgress {
+            transfer_id: transfer_id.to_string(),
+            direction: direction.to_string(),
+            current_file: String::new(),
+            files_done: 0,
+            files_total: 0,
+            bytes_done: 0,
+            bytes_total: 0,
+            finished: true,
+            cancelled: false,
+            error: Some(e.clone()),
+        });
+    }
+
+    result
+}
+
+fn parse_mode(mode: &str) -> Result<u32, String> {
+    u32::from_str_radix(mode.trim().trim_start_matches("0o"), 8)
+        .ok()
+        .filter(|m| *m <= 0o7777)
+        .ok_or_else(|| format!("Permessi non validi: {} (usa formato ottale, es. 644)", mode))
+}
+
+fn remove_dir_recursive(sftp: &Sftp, dir: &Path) -> Result<(), String> {
+    let entries = sftp
+        .readdir(dir)
+        .map_err(|e| format!("Impossibile leggere {}: {}", dir.display(), e))?;
+
+    for (path, stat) in entries {
+        if stat.is_dir() && !stat.file_type().is_symlink() {
+            remove_dir_recursive(sftp, &path)?;
+        } else {
+            sftp.unlink(&path)
+                .map_err(|e| format!("Eliminazione {} fallita: {}", path.display(), e))?;
+        }
+    }
+
+    sftp.rmdir(dir)
+        .map_err(|e| format!("Rimozione directory {} fallita: {}", dir.display(), e))
+}
+
+enum TransferItem {
+    Dir { remote: PathBuf, local: PathBuf },
+    File { remote: PathBuf, local: PathBuf, size: u64 },
+}
+
+fn plan_remote_tree(sftp: &Sftp, remote: &Path, local: &Path, plan: &mut Vec<TransferItem>) -> Result<(), String> {
+    plan.push(TransferItem::Dir { remote: remote.to_path_buf(), local: local.to_path_buf() });
+
+    let entries = sftp
+        .readdir(remote)
+        .map_err(|e| format!("Impossibile leggere {}: {}", remote.display(), e))?;
+
+    for (path, stat) in entries {
+        let Some(name) = path.file_name() else { continue };
+        let local_child = local.join(name);
+
+        if stat.file_type().is_symlink() {
+            continue; // i link simbolici non vengono seguiti
+        } else if stat.is_dir() {
+            plan_remote_tree(sftp, &path, &local_child, plan)?;
+        } else {
+            plan.push(TransferItem::File { remote: path, local: local_child, size: stat.size.unwrap_or(0) });
+        }
+    }
+
+    Ok(())
+}
+
+fn plan_local_tree(local: &Path, remote: &Path, plan: &mut Vec<TransferItem>) -> Result<(), String> {
+    plan.push(TransferItem::Dir { remote: remote.to_path_buf(), local: local.to_path_buf() });
+
+    let entries = fs::read_dir(local).map_err(|e| format!("Impossibile leggere {}: {}", local.display(), e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let file_type = entry.file_type().map_err(|e| e.to_string())?;
+        let remote_child = remote.join(entry.file_name());
+
+        if file_type.is_symlink() {
+            continue;
+        } else if file_type.is_dir() {
+            plan_local_tree(&entry.path(), &remote_child, plan)?;
+        } else {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            plan.push(TransferItem::File { remote: remote_child, local: entry.path(), size });
+        }
+    }
+
+    Ok(())
+}
+
+struct ProgressTracker<'a> {
+    app: &'a AppHandle,
+    progress: TransferProgress,
+    last_emit: Option<Instant>,
+}
+
+impl<'a> ProgressTracker<'a> {
+    fn new(app: &'a AppHandle, transfer_id: &str, direction: &str, plan: &[TransferItem]) -> Self {
+        let (files_total, bytes_total) = plan.iter().fold((0u32, 0u64), |(files, bytes), item| match item {
+            TransferItem::File { size, .. } => (files + 1, bytes + size),
+            TransferItem::Dir { .. } => (files, bytes),
+        });
+
+        Self {
+            app,
+            progress: TransferProgress {
+                transfer_id: transfer_id.to_string(),
+                direction: direction.to_string(),
+                current_file: String::new(),
+                files_done: 0,
+                files_total,
+                bytes_done: 0,
+                bytes_total,
+                finished: false,
+                cancelled: false,
+                error: None,
+            },
+            last_emit: None,
+        }
+    }
+
+    fn advance(&mut self, file: &str, bytes: u64) {
+        self.progress.current_file = file.to_string();
+        self.progress.bytes_done += bytes;
+        if self.last_emit.map_or(true, |t| t.elapsed() >= PROGRESS_INTERVAL) {
+            self.emit();
+        }
+    }
+
+    fn file_done(&mut self) {
+        self.progress.files_done += 1;
+        self.emit();
+    }
+
+    fn emit(&mut self) {
+        let _ = self.app.emit("sftp_progress", self.progress.clone());
+        self.last_emit = Some(Instant::now());
+    }
+
+    fn finish(mut self, cancelled: bool) -> TransferResult {
+        self.progress.finished = true;
+        self.progress.cancelled = cancelled;
+        self.emit();
+
+        TransferResult {
+            transfer_id: self.progress.transfer_id.clone(),
+            files: self.progress.files_done,
+            bytes: self.progress.bytes_done,
+            cancelled,
+        }
+    }
+}
+
+// Copia a blocchi con progress; ritorna false se il trasferimento è stato annullato
+fn copy_stream(
+    source: &mut impl Read,
+    target: &mut impl Write,
+    label: &str,
+    cancel: &AtomicBool,
+    tracker: &mut ProgressTracker,
+) -> Result<bool, String> {
+    let mut buf = vec![0u8; CHUNK_SIZE];
+
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
+
+        let n = source.read(&mut buf).map_err(|e| format!("Errore lettura {}: {}", label, e))?;
+        if n == 0 {
+            break;
+        }
+        target
+            .write_all(&buf[..n])
+            .map_err(|e| format!("Errore scrittura {}: {}", label, e))?;
+        tracker.advance(label, n as u64);
+    }
+
+    target.flush().map_err(|e| format!("Errore scrittura {}: {}", label, e))?;
+    tracker.file_done();
+    Ok(true)
+}
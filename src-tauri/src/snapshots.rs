@@ -0,0 +1,369 @@
+// src-tauri/src/snapshots.rs
+// Restore point automatici dei dati locali (server, snippet, job, gruppi, runbook)
+// Creati prima di ogni modifica e una volta al giorno, in snapshots/<id>.json
+// Retention: ultimi N, uno al giorno per una settimana, uno a settimana per un mese
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, TimeZone};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{command, AppHandle};
+
+use crate::scheduler::{replace_scheduled_jobs, ScheduledJob};
+use crate::{app_data_file, read_json_file, write_json_file};
+
+const SNAPSHOT_DIR: &str = "snapshots";
+const POLICY_FILE: &str = "snapshot-policy.json";
+const TRACKED_FILES: &[&str] = &["servers.json", "snippets.json", "schedules.json", "groups.json", "runbooks.json"];
+const LEGACY_PREFIX: &str = "servers-backup-";
+const DAILY_CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+
+// Creazione e pulizia non devono sovrapporsi
+static SNAPSHOT_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+static DAILY_STARTED: OnceCell<()> = OnceCell::new();
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetentionPolicy {
+    pub keep_last: usize,                // ultimi N restore point, sempre conservati
+    pub daily_days: i64,                 // il più recente di ogni giorno per N giorni
+    pub weekly_weeks: i64,               // il più recente di ogni settimana per N settimane
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self { keep_last: 10, daily_days: 7, weekly_weeks: 4 }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct Snapshot {
+    id: String,
+    created_at: String,
+    kind: String,                        // "auto" | "daily" | "manual"
+    reason: String,
+    checksum: String,
+    files: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RestorePoint {
+    pub id: String,
+    pub created_at: String,
+    pub kind: String,
+    pub reason: String,
+    pub summary: BTreeMap<String, usize>, // elementi per file ("servers" -> 12)
+    pub size_bytes: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RollbackResult {
+    pub restored: RestorePoint,
+    pub safety_point_id: Option<String>, // stato precedente al rollback
+}
+
+fn snapshot_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app_data_file(app, SNAPSHOT_DIR)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+fn checksum(files: &BTreeMap<String, serde_json::Value>) -> String {
+    let bytes = serde_json::to_vec(files).unwrap_or_default();
+    Sha256::digest(&bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn created_at(snapshot: &Snapshot) -> DateTime<Local> {
+    DateTime::parse_from_rfc3339(&snapshot.created_at)
+        .map(|t| t.with_timezone(&Local))
+        .unwrap_or_else(|_| Local::now())
+}
+
+// Stato attuale dei file tracciati (un file assente equivale a una lista vuota)
+fn current_files(app: &AppHandle) -> Result<BTreeMap<String, serde_json::Value>, String> {
+    let mut files = BTreeMap::new();
+    for name in TRACKED_FILES {
+        let path = app_data_file(app, name)?;
+        let value = if path.exists() {
+            let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+            match serde_json::from_str(&content) {
+                Ok(value) => value,
+                Err(e) => {
+                    println!("⚠️ {} non valido, escluso dal restore point: {}", name, e);
+                    continue;
+                }
+            }
+        } else {
+            serde_json::Value::Array(Vec::new())
+        };
+        files.insert(name.to_string(), value);
+    }
+    Ok(files)
+}
+
+// Tutti i restore point, dal più recente
+fn load_snapshots(app: &AppHandle) -> Result<Vec<(PathBuf, Snapshot)>, String> {
+    let dir = snapshot_dir(app)?;
+    let mut snapshots: Vec<(PathBuf, Snapshot)> = fs::read_dir(&dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().map_or(false, |ext| ext == "json"))
+        .filter_map(|path| {
+            let content = fs::read_to_string(&path).ok()?;
+            let snapshot = serde_json::from_str(&content).ok()?;
+            Some((path, snapshot))
+        })
+        .collect();
+    snapshots.sort_by_key(|(_, s)| std::cmp::Reverse(created_at(s)));
+    Ok(snapshots)
+}
+
+fn to_restore_point(path: &Path, snapshot: &Snapshot) -> RestorePoint {
+    let summary = snapshot
+        .files
+        .iter()
+        .map(|(name, value)| {
+            let key = name.trim_end_matches(".json").to_string();
+            (key, value.as_array().map_or(0, |a| a.len()))
+        })
+        .collect();
+
+    RestorePoint {
+        id: snapshot.id.clone(),
+        created_at: snapshot.created_at.clone(),
+        kind: snapshot.kind.clone(),
+        reason: snapshot.reason.clone(),
+        summary,
+        size_bytes: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+    }
+}
+
+fn write_snapshot(
+    app: &AppHandle,
+    kind: &str,
+    reason: &str,
+    at: DateTime<Local>,
+    files: BTreeMap<String, serde_json::Value>,
+) -> Result<Snapshot, String> {
+    let dir = snapshot_dir(app)?;
+    let base = format!("snapshot-{}", at.format("%Y%m%d-%H%M%S-%3f"));
+    let mut id = base.clone();
+    let mut n = 1;
+    while dir.join(format!("{}.json", id)).exists() {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+
+    let snapshot = Snapshot {
+        id: id.clone(),
+        created_at: at.to_rfc3339(),
+        kind: kind.to_string(),
+        reason: reason.to_string(),
+        checksum: checksum(&files),
+        files,
+    };
+    let json = serde_json::to_string(&snapshot).map_err(|e| e.to_string())?;
+    fs::write(dir.join(format!("{}.json", id)), json).map_err(|e| format!("Errore restore point: {}", e))?;
+    Ok(snapshot)
+}
+
+// Crea un restore point; se force=false e nulla è cambiato dall'ultimo, non crea duplicati
+fn create_snapshot(app: &AppHandle, kind: &str, reason: &str, force: bool) -> Result<Option<Snapshot>, String> {
+    let _guard = SNAPSHOT_LOCK.lock().unwrap();
+    let files = current_files(app)?;
+
+    if !force {
+        let latest = load_snapshots(app)?.into_iter().next();
+        if latest.map_or(false, |(_, s)| s.checksum == checksum(&files)) {
+            return Ok(None);
+        }
+    }
+
+    let snapshot = write_snapshot(app, kind, reason, Local::now(), files)?;
+    prune_snapshots(app, &load_policy(app))?;
+    Ok(Some(snapshot))
+}
+
+// ✅ Da chiamare prima di ogni scrittura dei dati dell'utente
+pub fn snapshot_before_change(app: &AppHandle, reason: &str) -> Result<(), String> {
+    create_snapshot(app, "auto", reason, false).map(|_| ())
+}
+
+fn load_policy(app: &AppHandle) -> RetentionPolicy {
+    read_json_file(app, POLICY_FILE).unwrap_or_default()
+}
+
+// Elimina i restore point non coperti dalla retention
+fn prune_snapshots(app: &AppHandle, policy: &RetentionPolicy) -> Result<usize, String> {
+    let now = Local::now();
+    let mut days = HashSet::new();
+    let mut weeks = HashSet::new();
+    let mut removed = 0;
+
+    for (index, (path, snapshot)) in load_snapshots(app)?.iter().enumerate() {
+        let at = created_at(snapshot);
+        let age_days = (now - at).num_days();
+
+        let recent = index < policy.keep_last;
+        let daily = age_days < policy.daily_days && days.insert(at.date_naive());
+        let weekly = age_days < policy.weekly_weeks * 7 && weeks.insert((at.iso_week().year(), at.iso_week().week()));
+
+        if !(recent || daily || weekly) {
+            fs::remove_file(path).map_err(|e| e.to_string())?;
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
+// I vecchi servers-backup-YYYYMMDD-HHMMSS.json diventano restore point (e rientrano nella retention)
+fn migrate_legacy_backups(app: &AppHandle) -> Result<usize, String> {
+    let data_dir = app_data_file(app, "")?;
+    let mut migrated = 0;
+
+    for entry in fs::read_dir(&data_dir).map_err(|e| e.to_string())?.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(stamp) = name.strip_prefix(LEGACY_PREFIX).and_then(|n| n.strip_suffix(".json")) else { continue };
+
+        let Some(at) = NaiveDateTime::parse_from_str(stamp, "%Y%m%d-%H%M%S")
+            .ok()
+            .and_then(|t| Local.from_local_datetime(&t).earliest())
+        else {
+            continue;
+        };
+        let Ok(servers) = fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|c| {
+            serde_json::from_str::<serde_json::Value>(&c).map_err(|e| e.to_string())
+        }) else {
+            println!("⚠️ Backup {} non leggibile, lasciato al suo posto", name);
+            continue;
+        };
+
+        let files = BTreeMap::from([("servers.json".to_string(), servers)]);
+        write_snapshot(app, "auto", "backup prima di un import", at, files)?;
+        fs::remove_file(&path).map_err(|e| e.to_string())?;
+        migrated += 1;
+    }
+
+    Ok(migrated)
+}
+
+// ✅ Avviato al setup: migrazione dei vecchi backup e restore point giornaliero
+pub fn start_snapshot_scheduler(app: AppHandle) {
+    if DAILY_STARTED.set(()).is_err() {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        {
+            let _guard = SNAPSHOT_LOCK.lock().unwrap();
+            match migrate_legacy_backups(&app) {
+                Ok(0) => {}
+                Ok(count) => println!("🗂️ {} backup precedenti convertiti in restore point", count),
+                Err(e) => println!("⚠️ Migrazione backup fallita: {}", e),
+            }
+        }
+
+        let mut interval = tokio::time::interval(DAILY_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let today = Local::now().date_naive();
+            let done_today = load_snapshots(&app)
+                .map(|list| list.iter().any(|(_, s)| s.kind == "daily" && created_at(s).date_naive() == today))
+                .unwrap_or(false);
+
+            if !done_today {
+                match create_snapshot(&app, "daily", "restore point giornaliero", true) {
+                    Ok(_) => println!("🗂️ Restore point giornaliero creato"),
+                    Err(e) => println!("⚠️ Restore point giornaliero fallito: {}", e),
+                }
+            }
+        }
+    });
+}
+
+// ✅ COMANDO: Restore point disponibili, dal più recente
+#[command]
+pub async fn list_restore_points(app: AppHandle) -> Result<Vec<RestorePoint>, String> {
+    Ok(load_snapshots(&app)?.iter().map(|(path, s)| to_restore_point(path, s)).collect())
+}
+
+// ✅ COMANDO: Restore point manuale
+#[command]
+pub async fn create_restore_point(app: AppHandle, reason: Option<String>) -> Result<RestorePoint, String> {
+    let reason = reason.filter(|r| !r.trim().is_empty()).unwrap_or_else(|| "restore point manuale".to_string());
+    let snapshot = create_snapshot(&app, "manual", &reason, true)?.ok_or("Restore point non creato")?;
+    let path = snapshot_dir(&app)?.join(format!("{}.json", snapshot.id));
+    Ok(to_restore_point(&path, &snapshot))
+}
+
+// ✅ COMANDO: Ripristina i file contenuti nel restore point (lo stato attuale viene salvato prima)
+#[command]
+pub async fn rollback_to_restore_point(app: AppHandle, restore_point_id: String) -> Result<RollbackResult, String> {
+    if restore_point_id.contains(['/', '\\']) || restore_point_id.contains("..") {
+        return Err("Id restore point non valido".to_string());
+    }
+
+    let path = snapshot_dir(&app)?.join(format!("{}.json", restore_point_id));
+    let content = fs::read_to_string(&path).map_err(|_| format!("Restore point '{}' non trovato", restore_point_id))?;
+    let snapshot: Snapshot = serde_json::from_str(&content).map_err(|e| format!("Restore point non valido: {}", e))?;
+    if snapshot.checksum != checksum(&snapshot.files) {
+        return Err("Restore point danneggiato (checksum non corrispondente)".to_string());
+    }
+
+    // I job vanno deserializzati prima di scrivere qualsiasi file
+    let jobs: Option<Vec<ScheduledJob>> = match snapshot.files.get("schedules.json") {
+        Some(value) => Some(serde_json::from_value(value.clone()).map_err(|e| format!("schedules.json non valido: {}", e))?),
+        None => None,
+    };
+
+    let safety = create_snapshot(&app, "auto", &format!("prima del rollback a {}", restore_point_id), false)?;
+
+    for (name, value) in &snapshot.files {
+        match name.as_str() {
+            "schedules.json" => {}
+            name if TRACKED_FILES.contains(&name) => write_json_file(&app, name, value)?,
+            other => println!("⚠️ File {} nel restore point ignorato", other),
+        }
+    }
+    if let Some(jobs) = jobs {
+        replace_scheduled_jobs(&app, &jobs)?;
+    }
+
+    println!("⏪ Rollback al restore point {}", restore_point_id);
+    Ok(RollbackResult {
+        restored: to_restore_point(&path, &snapshot),
+        safety_point_id: safety.map(|s| s.id),
+    })
+}
+
+// ✅ COMANDO: Policy di retention attuale
+#[command]
+pub async fn get_snapshot_policy(app: AppHandle) -> Result<RetentionPolicy, String> {
+    Ok(load_policy(&app))
+}
+
+// ✅ COMANDO: Aggiorna la policy e applica subito la pulizia; ritorna i restore point eliminati
+#[command]
+pub async fn set_snapshot_policy(app: AppHandle, policy: RetentionPolicy) -> Result<usize, String> {
+    if policy.keep_last == 0 {
+        return Err("Serve almeno un restore point recente (keepLast >= 1)".to_string());
+    }
+    if policy.daily_days < 0 || policy.weekly_weeks < 0 {
+        return Err("Valori di retention non validi".to_string());
+    }
+
+    write_json_file(&app, POLICY_FILE, &policy)?;
+    let _guard = SNAPSHOT_LOCK.lock().unwrap();
+    prune_snapshots(&app, &policy)
+}
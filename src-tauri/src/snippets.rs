@@ -0,0 +1,228 @@
+// src-tauri/src/snippets.rs
+// Libreria di snippet di comandi con parametri `{{nome}}` / `{{nome:default}}`
+// Salvati in snippets.json accanto a servers.json
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle};
+
+use crate::remote_exec::{execute_on_server, ExecOptions, RemoteCommandResult};
+use crate::snapshots::snapshot_before_change;
+use crate::session_registry::write_to_session_shell;
+use crate::ssh_session::find_server;
+use crate::{new_id, read_json_file, write_json_file};
+
+const SNIPPETS_FILE: &str = "snippets.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub command: String,                 // template con {{placeholder}}
+    #[serde(default)]
+    pub default_tags: Vec<String>,       // tag dei server su cui lo snippet si usa di solito
+    #[serde(default)]
+    pub requires_sudo: bool,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetPlaceholder {
+    pub name: String,
+    pub default_value: Option<String>,
+}
+
+pub fn load_snippets(app: &AppHandle) -> Result<Vec<Snippet>, String> {
+    read_json_file(app, SNIPPETS_FILE)
+}
+
+pub fn store_snippets(app: &AppHandle, snippets: &[Snippet]) -> Result<(), String> {
+    write_json_file(app, SNIPPETS_FILE, &snippets)
+}
+
+fn find_snippet(app: &AppHandle, snippet_id: &str) -> Result<Snippet, String> {
+    load_snippets(app)?
+        .into_iter()
+        .find(|s| s.id == snippet_id)
+        .ok_or_else(|| format!("Snippet '{}' non trovato", snippet_id))
+}
+
+// ✅ Placeholder presenti nel template, nell'ordine in cui compaiono (senza duplicati)
+pub fn snippet_placeholders(template: &str) -> Vec<SnippetPlaceholder> {
+    let mut placeholders: Vec<SnippetPlaceholder> = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else { break };
+        let inner = rest[start + 2..start + 2 + end].trim();
+        rest = &rest[start + 2 + end + 2..];
+
+        let (name, default_value) = match inner.split_once(':') {
+            Some((name, default)) => (name.trim(), Some(default.to_string())),
+            None => (inner, None),
+        };
+        if !name.is_empty() && !placeholders.iter().any(|p| p.name == name) {
+            placeholders.push(SnippetPlaceholder { name: name.to_string(), default_value });
+        }
+    }
+
+    placeholders
+}
+
+// ✅ Sostituisce i placeholder; errore se manca un valore senza default
+pub fn render_template(template: &str, params: &HashMap<String, String>) -> Result<String, String> {
+    let missing: Vec<String> = snippet_placeholders(template)
+        .into_iter()
+        .filter(|p| p.default_value.is_none() && !params.contains_key(&p.name))
+        .map(|p| p.name)
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("Parametri mancanti: {}", missing.join(", ")));
+    }
+
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else { break };
+        let inner = rest[start + 2..start + 2 + end].trim();
+        let (name, default_value) = match inner.split_once(':') {
+            Some((name, default)) => (name.trim(), Some(default)),
+            None => (inner, None),
+        };
+
+        rendered.push_str(&rest[..start]);
+        match params.get(name).map(|v| v.as_str()).or(default_value) {
+            Some(value) => rendered.push_str(value),
+            None => rendered.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &rest[start + 2 + end + 2..];
+    }
+    rendered.push_str(rest);
+
+    Ok(rendered)
+}
+
+// ✅ COMANDO: Elenco snippet
+#[command]
+pub async fn list_snippets(app: AppHandle) -> Result<Vec<Snippet>, String> {
+    load_snippets(&app)
+}
+
+// ✅ COMANDO: Crea o aggiorna uno snippet (id vuoto = nuovo)
+#[command]
+pub async fn save_snippet(app: AppHandle, mut snippet: Snippet) -> Result<Snippet, String> {
+    if snippet.name.trim().is_empty() {
+        return Err("Il nome dello snippet è obbligatorio".to_string());
+    }
+    if snippet.command.trim().is_empty() {
+        return Err("Il comando dello snippet è obbligatorio".to_string());
+    }
+
+    let mut snippets = load_snippets(&app)?;
+    let now = chrono::Local::now().to_rfc3339();
+    snippet.updated_at = now.clone();
+
+    match snippets.iter_mut().find(|s| !snippet.id.is_empty() && s.id == snippet.id) {
+        Some(existing) => {
+            snippet.created_at = existing.created_at.clone();
+            *existing = snippet.clone();
+        }
+        None => {
+            if snippet.id.is_empty() {
+                snippet.id = new_id("snippet");
+            }
+            snippet.created_at = now;
+            snippets.push(snippet.clone());
+        }
+    }
+
+    snapshot_before_change(&app, "salvataggio snippet")?;
+    write_json_file(&app, SNIPPETS_FILE, &snippets)?;
+    Ok(snippet)
+}
+
+// ✅ COMANDO: Elimina uno snippet
+#[command]
+pub async fn delete_snippet(app: AppHandle, snippet_id: String) -> Result<(), String> {
+    let mut snippets = load_snippets(&app)?;
+    let before = snippets.len();
+    snippets.retain(|s| s.id != snippet_id);
+    if snippets.len() == before {
+        return Err(format!("Snippet '{}' non trovato", snippet_id));
+    }
+    snapshot_before_change(&app, "eliminazione snippet")?;
+    write_json_file(&app, SNIPPETS_FILE, &snippets)
+}
+
+// ✅ COMANDO: Placeholder di uno snippet (per costruire il form dei parametri)
+#[command]
+pub async fn get_snippet_placeholders(app: AppHandle, snippet_id: String) -> Result<Vec<SnippetPlaceholder>, String> {
+    Ok(snippet_placeholders(&find_snippet(&app, &snippet_id)?.command))
+}
+
+// ✅ COMANDO: Anteprima del comando con i parametri applicati
+#[command]
+pub async fn render_snippet(
+    app: AppHandle,
+    snippet_id: String,
+    params: Option<HashMap<String, String>>,
+) -> Result<String, String> {
+    let snippet = find_snippet(&app, &snippet_id)?;
+    render_template(&snippet.command, &params.unwrap_or_default())
+}
+
+// ✅ COMANDO: Esegue lo snippet su un server
+#[command]
+pub async fn execute_snippet(
+    app: AppHandle,
+    server_id: String,
+    snippet_id: String,
+    params: Option<HashMap<String, String>>,
+    options: Option<ExecOptions>,
+) -> Result<RemoteCommandResult, String> {
+    let snippet = find_snippet(&app, &snippet_id)?;
+    let command = render_template(&snippet.command, &params.unwrap_or_default())?;
+    let (server, servers) = find_server(&app, &server_id).await?;
+
+    let mut options = options.unwrap_or_default();
+    if snippet.requires_sudo && server.ssh_user != "root" {
+        options.sudo = Some(true);
+    }
+
+    println!("📎 Snippet '{}' su {}", snippet.name, server.name);
+    execute_on_server(&app, server, servers, command, options).await
+}
+
+// ✅ COMANDO: Inserisce lo snippet nella shell di una sessione SSH aperta (start_ssh_session)
+// Senza `execute` il comando resta sulla riga, pronto da modificare; l'output arriva con "ssh_session_output"
+#[command]
+pub async fn inject_snippet_into_terminal(
+    app: AppHandle,
+    session_id: String,
+    snippet_id: String,
+    params: Option<HashMap<String, String>>,
+    execute: Option<bool>,
+) -> Result<String, String> {
+    let snippet = find_snippet(&app, &snippet_id)?;
+    let text = render_template(&snippet.command, &params.unwrap_or_default())?;
+
+    let mut input = text.clone();
+    if execute.unwrap_or(false) {
+        input.push('\r');
+    }
+
+    tokio::task::spawn_blocking(move || write_to_session_shell(&app, &session_id, input.as_bytes()))
+        .await
+        .map_err(|e| format!("Errore task terminale: {}", e))??;
+
+    Ok(text)
+}
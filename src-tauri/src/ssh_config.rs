@@ -0,0 +1,370 @@
+// src-tauri/src/ssh_config.rs
+// Importazione dei server da ~/.ssh/config (OpenSSH)
+// Supporta Host con più alias, HostName, User, Port, IdentityFile, ProxyJump, Include
+// e i blocchi con wildcard ("Host *", "Host *.prod") come valori di default
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle};
+
+use crate::server_import::{apply_preview, build_preview, ImportPreview, MergeMode};
+use crate::{load_servers, Server};
+
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+struct HostBlock {
+    patterns: Vec<String>,
+    options: Vec<(String, String)>,      // chiave in minuscolo, valore così com'è
+}
+
+pub(crate) fn home_dir() -> Option<PathBuf> {
+    std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")).ok().map(PathBuf::from)
+}
+
+fn expand_tilde(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ if path == "~" => home_dir().unwrap_or_else(|| PathBuf::from(path)),
+        _ => PathBuf::from(path),
+    }
+}
+
+// Pattern OpenSSH: "*" e "?" come jolly, confronto case-insensitive
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    fn matches(p: &[char], t: &[char]) -> bool {
+        match (p.first(), t.first()) {
+            (None, None) => true,
+            (Some('*'), _) => matches(&p[1..], t) || (!t.is_empty() && matches(p, &t[1..])),
+            (Some('?'), Some(_)) => matches(&p[1..], &t[1..]),
+            (Some(a), Some(b)) => a.eq_ignore_ascii_case(b) && matches(&p[1..], &t[1..]),
+            _ => false,
+        }
+    }
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    matches(&p, &t)
+}
+
+fn is_concrete_alias(pattern: &str) -> bool {
+    !pattern.contains(['*', '?', '!'])
+}
+
+impl HostBlock {
+    fn applies_to(&self, alias: &str) -> bool {
+        let negated = self
+            .patterns
+            .iter()
+            .filter_map(|p| p.strip_prefix('!'))
+            .any(|p| wildcard_match(p, alias));
+        !negated && self.patterns.iter().any(|p| !p.starts_with('!') && wildcard_match(p, alias))
+    }
+}
+
+// "Key value", "Key=value", "Key = \"value con spazi\""
+fn split_directive(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let split_at = line.find(|c: char| c.is_whitespace() || c == '=')?;
+    let key = line[..split_at].to_lowercase();
+    let value = line[split_at..].trim_start_matches(|c: char| c.is_whitespace() || c == '=').trim();
+    let value = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value);
+    Some((key, value.to_string()))
+}
+
+// File indicati da un Include (relativi a ~/.ssh, con wildcard nel nome del file)
+fn include_targets(value: &str) -> Vec<PathBuf> {
+    let ssh_dir = home_dir().map(|h| h.join(".ssh")).unwrap_or_default();
+    let mut targets = Vec::new();
+
+    for entry in value.split_whitespace() {
+        let path = expand_tilde(entry);
+        let path = if path.is_absolute() { path } else { ssh_dir.join(path) };
+
+        let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        if !file_name.contains(['*', '?']) {
+            targets.push(path);
+            continue;
+        }
+
+        let Some(dir) = path.parent() else { continue };
+        let mut matched: Vec<PathBuf> = fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.path())
+                    .filter(|p| p.is_file())
+                    .filter(|p| p.file_name().map_or(false, |n| wildcard_match(&file_name, &n.to_string_lossy())))
+                    .collect()
+            })
+            .unwrap_or_default();
+        matched.sort();
+        targets.extend(matched);
+    }
+
+    targets
+}
+
+// Legge il file espandendo gli Include nel punto in cui compaiono
+fn collect_directives(path: &Path, depth: usize, out: &mut Vec<(String, String)>, warnings: &mut Vec<String>) -> Result<(), String> {
+    if depth > MAX_INCLUDE_DEPTH {
+        warnings.push(format!("Include troppo annidati, ignorato {}", path.display()));
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(path).map_err(|e| format!("Impossibile leggere {}: {}", path.display(), e))?;
+    for (key, value) in content.lines().filter_map(split_directive) {
+        if key == "include" {
+            for target in include_targets(&value) {
+                if let Err(e) = collect_directives(&target, depth + 1, out, warnings) {
+                    warnings.push(e);
+                }
+            }
+        } else {
+            out.push((key, value));
+        }
+    }
+    Ok(())
+}
+
+fn parse_blocks(directives: Vec<(String, String)>, warnings: &mut Vec<String>) -> Vec<HostBlock> {
+    // Le righe prima del primo Host valgono per tutti
+    let mut blocks = vec![HostBlock { patterns: vec!["*".to_string()], options: Vec::new() }];
+    let mut warned_match = false;
+
+    for (key, value) in directives {
+        match key.as_str() {
+            "host" => blocks.push(HostBlock {
+                patterns: value.split_whitespace().map(|p| p.to_string()).collect(),
+                options: Vec::new(),
+            }),
+            "match" => {
+                if !warned_match {
+                    warnings.push("I blocchi Match non sono supportati e vengono ignorati".to_string());
+                    warned_match = true;
+                }
+                blocks.push(HostBlock { patterns: Vec::new(), options: Vec::new() });
+            }
+            _ => blocks.last_mut().unwrap().options.push((key, value)),
+        }
+    }
+
+    blocks
+}
+
+// Come OpenSSH: per ogni opzione vale il primo valore trovato tra i blocchi applicabili
+fn resolve_options(alias: &str, blocks: &[HostBlock]) -> HashMap<String, String> {
+    let mut options = HashMap::new();
+    for block in blocks.iter().filter(|b| b.applies_to(alias)) {
+        for (key, value) in &block.options {
+            options.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+    options
+}
+
+// "user@host:port" -> "host"
+fn jump_hop_host(hop: &str) -> String {
+    let without_user = hop.rsplit_once('@').map_or(hop, |(_, h)| h);
+    if let Some(rest) = without_user.strip_prefix('[') {
+        return rest.split(']').next().unwrap_or(rest).to_string();
+    }
+    without_user.split(':').next().unwrap_or(without_user).to_string()
+}
+
+// ✅ Converte un ssh_config in record Server (non ancora salvati)
+pub fn parse_ssh_config(path: &Path) -> Result<(Vec<Server>, Vec<String>), String> {
+    let mut warnings = Vec::new();
+    let mut directives = Vec::new();
+    collect_directives(path, 0, &mut directives, &mut warnings)?;
+    let blocks = parse_blocks(directives, &mut warnings);
+
+    let mut aliases: Vec<String> = Vec::new();
+    for pattern in blocks.iter().flat_map(|b| b.patterns.iter()) {
+        if is_concrete_alias(pattern) && !aliases.contains(pattern) {
+            aliases.push(pattern.clone());
+        }
+    }
+
+    let default_user = std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_else(|_| "root".to_string());
+    let mut servers = Vec::new();
+
+    for alias in aliases {
+        let options = resolve_options(&alias, &blocks);
+
+        let ip = options.get("hostname").map(|h| h.replace("%h", &alias)).unwrap_or_else(|| alias.clone());
+        let ssh_port = match options.get("port") {
+            Some(port) => match port.parse() {
+                Ok(port) => port,
+                Err(_) => {
+                    warnings.push(format!("{}: porta non valida '{}', uso 22", alias, port));
+                    22
+                }
+            },
+            None => 22,
+        };
+
+        let ssh_key_path = options.get("identityfile").map(|f| {
+            let f = home_dir().map_or(f.clone(), |h| f.replace("%d", &h.to_string_lossy()));
+            expand_tilde(&f).to_string_lossy().to_string()
+        });
+
+        let jump_host = match options.get("proxyjump").map(|j| j.trim()) {
+            Some(jump) if !jump.eq_ignore_ascii_case("none") => {
+                let hops: Vec<&str> = jump.split(',').map(|h| h.trim()).collect();
+                if hops.len() > 1 {
+                    warnings.push(format!(
+                        "{}: ProxyJump con più hop, uso '{}' (la catena deve essere configurata sui jump host)",
+                        alias,
+                        hops[hops.len() - 1]
+                    ));
+                }
+                hops.last().map(|h| jump_hop_host(h))
+            }
+            _ => None,
+        };
+        if options.contains_key("proxycommand") {
+            warnings.push(format!("{}: ProxyCommand non supportato, ignorato", alias));
+        }
+
+        servers.push(Server {
+            id: String::new(),
+            name: alias.clone(),
+            ip,
+            ssh_user: options.get("user").cloned().unwrap_or_else(|| default_user.clone()),
+            ssh_port,
+            auth_method: "key".to_string(),
+            ssh_key_path,
+            server_type: "Custom".to_string(),
+            status: "offline".to_string(),
+            jump_host,
+            ..Default::default()
+        });
+    }
+
+    Ok((servers, warnings))
+}
+
+fn config_path(path: Option<String>) -> Result<PathBuf, String> {
+    match path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => Ok(expand_tilde(&path)),
+        None => home_dir()
+            .map(|h| h.join(".ssh").join("config"))
+            .ok_or_else(|| "Cartella home non trovata".to_string()),
+    }
+}
+
+async fn prepare_import(
+    app: &AppHandle,
+    path: Option<String>,
+    hosts: Option<Vec<String>>,
+    mode: Option<String>,
+) -> Result<(Vec<Server>, ImportPreview), String> {
+    let mode = MergeMode::parse(mode.as_deref(), MergeMode::UpsertHost)?;
+    let path = config_path(path)?;
+    let (mut incoming, warnings) = parse_ssh_config(&path)?;
+    if let Some(hosts) = hosts {
+        incoming.retain(|s| hosts.contains(&s.name));
+    }
+
+    let existing = load_servers(app.clone()).await?;
+    let preview = build_preview(&path.to_string_lossy(), &existing, incoming, warnings, Vec::new(), mode, false);
+    Ok((existing, preview))
+}
+
+// ✅ COMANDO: Anteprima dei server che verrebbero creati / aggiornati
+#[command]
+pub async fn preview_ssh_config_import(app: AppHandle, path: Option<String>, mode: Option<String>) -> Result<ImportPreview, String> {
+    Ok(prepare_import(&app, path, None, mode).await?.1)
+}
+
+// ✅ COMANDO: Importa (eventualmente solo gli alias scelti nell'anteprima)
+#[command]
+pub async fn import_ssh_config(
+    app: AppHandle,
+    path: Option<String>,
+    hosts: Option<Vec<String>>,
+    mode: Option<String>,
+) -> Result<ImportPreview, String> {
+    let (existing, preview) = prepare_import(&app, path, hosts, mode).await?;
+    apply_preview(&app, existing, preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // File temporanei senza dipendenze extra: una cartella per test, rimossa alla fine
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("devpulse-ssh-config-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn server<'a>(servers: &'a [Server], name: &str) -> &'a Server {
+        servers.iter().find(|s| s.name == name).unwrap()
+    }
+
+    #[test]
+    fn first_value_wins_and_wildcards_are_not_servers() {
+        let dir = temp_dir("basic");
+        let config = dir.join("config");
+        fs::write(
+            &config,
+            "User global\n\
+             Host web1 web2\n  HostName %h.example.com\n  Port=2222\n\
+             Host web*\n  User deploy\n  Port 22\n\
+             Host db !web*\n  HostName = \"10.0.0.5\"\n  ProxyJump admin@bastion:2200\n",
+        )
+        .unwrap();
+
+        let (servers, warnings) = parse_ssh_config(&config).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let names: Vec<&str> = servers.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["web1", "web2", "db"]);
+        assert!(warnings.is_empty(), "{:?}", warnings);
+
+        let web1 = server(&servers, "web1");
+        assert_eq!(web1.ip, "web1.example.com");
+        assert_eq!(web1.ssh_port, 2222);
+        // Le righe prima del primo Host hanno la precedenza, come in OpenSSH
+        assert_eq!(web1.ssh_user, "global");
+
+        let db = server(&servers, "db");
+        assert_eq!(db.ip, "10.0.0.5");
+        assert_eq!(db.ssh_port, 22);
+        assert_eq!(db.jump_host.as_deref(), Some("bastion"));
+    }
+
+    #[test]
+    fn expands_includes_and_reports_unsupported_directives() {
+        let dir = temp_dir("include");
+        let extra = dir.join("extra.conf");
+        fs::write(&extra, "Host included\n  HostName 192.168.1.10\n  ProxyCommand nc %h %p\n").unwrap();
+        let config = dir.join("config");
+        fs::write(
+            &config,
+            format!("Include {}\nHost bad-port\n  Port abc\nMatch host foo\n  User nobody\n", dir.join("*.conf").display()),
+        )
+        .unwrap();
+
+        let (servers, warnings) = parse_ssh_config(&config).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(server(&servers, "included").ip, "192.168.1.10");
+        assert_eq!(server(&servers, "bad-port").ssh_port, 22);
+        assert!(warnings.iter().any(|w| w.contains("ProxyCommand")));
+        assert!(warnings.iter().any(|w| w.contains("porta non valida")));
+        assert!(warnings.iter().any(|w| w.contains("Match")));
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        assert!(parse_ssh_config(Path::new("/nonexistent/devpulse/ssh_config")).is_err());
+    }
+}
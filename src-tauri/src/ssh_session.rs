@@ -0,0 +1,383 @@
+// src-tauri/src/ssh_session.rs
+// Livello di connessione SSH basato su ssh2 (libssh2)
+// Gestisce autenticazione e catene di jump host / bastion
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use ssh2::{Channel, Session};
+use tauri::AppHandle;
+
+use crate::{load_servers, Server};
+
+const CONNECT_TIMEOUT_SECS: u64 = 10;
+const SESSION_TIMEOUT_MS: u32 = 15_000;
+const MAX_JUMP_HOPS: usize = 8;
+
+// ✅ Risolve la catena di jump host seguendo i riferimenti `jump_host`
+// Ritorna gli hop in ordine di attraversamento (dal primo bastion all'ultimo)
+pub fn resolve_jump_chain(server: &Server, servers: &[Server]) -> Result<Vec<Server>, String> {
+    let mut chain: Vec<Server> = Vec::new();
+    let mut visited = vec![server.id.clone()];
+    let mut next = server.jump_host.clone();
+
+    while let Some(jump_id) = next.filter(|id| !id.trim().is_empty()) {
+        if visited.contains(&jump_id) {
+            return Err(format!("Ciclo nei jump host rilevato su '{}'", jump_id));
+        }
+        if chain.len() >= MAX_JUMP_HOPS {
+            return Err(format!("Troppi jump host (massimo {})", MAX_JUMP_HOPS));
+        }
+
+        let hop = servers
+            .iter()
+            .find(|s| s.id == jump_id)
+            .ok_or_else(|| format!("Jump host '{}' non trovato tra i server", jump_id))?;
+
+        visited.push(jump_id);
+        next = hop.jump_host.clone();
+        chain.push(hop.clone());
+    }
+
+    chain.reverse();
+    Ok(chain)
+}
+
+// ✅ Valore per `ssh -J` (ProxyJump) a partire dalla catena risolta
+pub fn proxy_jump_arg(chain: &[Server]) -> Option<String> {
+    if chain.is_empty() {
+        return None;
+    }
+
+    Some(
+        chain
+            .iter()
+            .map(|hop| format!("{}@{}:{}", hop.ssh_user, hop.ip, hop.ssh_port))
+            .collect::<Vec<_>>()
+            .join(","),
+    )
+}
+
+// ✅ Server salvato corrispondente alla destinazione (None se non è in inventario)
+// Cerca per id se indicato, altrimenti per ip/porta/utente: così i jump host valgono
+// anche quando il chiamante passa solo i dati di connessione
+pub async fn find_saved_target(
+    app: &AppHandle,
+    server_id: Option<&str>,
+    ip: &str,
+    ssh_port: u16,
+    ssh_user: &str,
+) -> Result<Option<(Server, Vec<Server>)>, String> {
+    let servers = load_servers(app.clone()).await?;
+    let server = server_id
+        .and_then(|id| servers.iter().find(|s| s.id == id))
+        .or_else(|| {
+            servers
+                .iter()
+                .find(|s| s.ip.eq_ignore_ascii_case(ip) && s.ssh_port == ssh_port && s.ssh_user == ssh_user)
+        })
+        .cloned();
+    Ok(server.map(|server| (server, servers)))
+}
+
+// ✅ Carica i server e ritorna quello richiesto insieme alla lista completa
+// (la lista serve per risolvere i jump host)
+pub async fn find_server(app: &AppHandle, server_id: &str) -> Result<(Server, Vec<Server>), String> {
+    let servers = load_servers(app.clone()).await?;
+    let server = servers
+        .iter()
+        .find(|s| s.id == server_id)
+        .cloned()
+        .ok_or_else(|| format!("Server '{}' non trovato", server_id))?;
+    Ok((server, servers))
+}
+
+// ✅ Apre una sessione ssh2 autenticata verso il server, attraversando i jump host
+// Bloccante: dai comandi async va chiamata dentro `spawn_blocking`
+pub fn open_session(server: &Server, servers: &[Server]) -> Result<Session, String> {
+    let chain = resolve_jump_chain(server, servers)?;
+
+    let mut hops = chain.iter().chain(std::iter::once(server));
+    let first = hops.next().ok_or("Catena SSH vuota")?;
+
+    let stream = connect_tcp(&first.ip, first.ssh_port)?;
+    let mut session = handshake_and_auth(stream, first)?;
+
+    for hop in hops {
+        println!("🪜 Jump verso {}@{}:{}", hop.ssh_user, hop.ip, hop.ssh_port);
+
+        let channel = session
+            .channel_direct_tcpip(&hop.ip, hop.ssh_port, None)
+            .map_err(|e| format!("Errore canale direct-tcpip verso {}: {}", hop.ip, e))?;
+
+        let local_addr = spawn_jump_forwarder(session, channel)?;
+        let stream = TcpStream::connect(local_addr)
+            .map_err(|e| format!("Errore connessione al forwarder locale: {}", e))?;
+
+        session = handshake_and_auth(stream, hop)?;
+    }
+
+    Ok(session)
+}
+
+// ✅ Estrae il testo completo dal buffer di output di un canale
+// L'ultimo carattere UTF-8 spezzato tra due letture resta nel buffer per la lettura successiva
+pub fn take_utf8(pending: &mut Vec<u8>) -> String {
+    let mut cut = pending.len();
+    for back in 1..=pending.len().min(3) {
+        let byte = pending[pending.len() - back];
+        if byte & 0xC0 != 0x80 {
+            let needed = match byte {
+                0xF0..=0xFF => 4,
+                0xE0..=0xEF => 3,
+                0xC0..=0xDF => 2,
+                _ => 1,
+            };
+            if needed > back {
+                cut = pending.len() - back;
+            }
+            break;
+        }
+    }
+
+    let text = String::from_utf8_lossy(&pending[..cut]).into_owned();
+    pending.drain(..cut);
+    text
+}
+
+fn connect_tcp(host: &str, port: u16) -> Result<TcpStream, String> {
+    let addr = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| format!("Indirizzo non valido {}:{}: {}", host, port, e))?
+        .next()
+        .ok_or_else(|| format!("Nessun indirizzo risolto per {}", host))?;
+
+    TcpStream::connect_timeout(&addr, Duration::from_secs(CONNECT_TIMEOUT_SECS))
+        .map_err(|e| format!("Connessione a {}:{} fallita: {}", host, port, e))
+}
+
+fn handshake_and_auth(stream: TcpStream, server: &Server) -> Result<Session, String> {
+    let mut session = Session::new().map_err(|e| format!("Errore creazione sessione SSH: {}", e))?;
+    session.set_tcp_stream(stream);
+    session.set_timeout(SESSION_TIMEOUT_MS);
+    session
+        .handshake()
+        .map_err(|e| format!("Handshake SSH con {} fallito: {}", server.ip, e))?;
+
+    authenticate(&session, server)?;
+    session.set_timeout(0);
+    Ok(session)
+}
+
+// ✅ Autenticazione secondo `auth_method` del server
+fn authenticate(session: &Session, server: &Server) -> Result<(), String> {
+    let user = server.ssh_user.as_str();
+
+    let result = if server.auth_method == "password" {
+        let password = server
+            .password
+            .as_deref()
+            .ok_or_else(|| format!("Password mancante per {}", server.name))?;
+        session.userauth_password(user, password)
+    } else if let Some(key_path) = server.ssh_key_path.as_deref().filter(|p| !p.is_empty()) {
+        session.userauth_pubkey_file(user, None, Path::new(key_path), None)
+    } else if !server.ssh_key.trim().is_empty() {
+        session.userauth_pubkey_memory(user, None, &server.ssh_key, None)
+    } else {
+        // Come OpenSSH: prima l'agent, poi le chiavi predefinite in ~/.ssh
+        session.userauth_agent(user).or_else(|agent_err| {
+            default_identity_files()
+                .iter()
+                .find_map(|key| session.userauth_pubkey_file(user, None, key, None).ok())
+                .ok_or(agent_err)
+        })
+    };
+
+    result.map_err(|e| format!("Autenticazione fallita su {}@{}: {}", user, server.ip, e))?;
+
+    if !session.authenticated() {
+        return Err(format!("Autenticazione rifiutata da {}", server.ip));
+    }
+
+    Ok(())
+}
+
+fn default_identity_files() -> Vec<PathBuf> {
+    let home = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE"));
+    let Ok(home) = home else {
+        return Vec::new();
+    };
+
+    ["id_ed25519", "id_ecdsa", "id_rsa"]
+        .iter()
+        .map(|name| Path::new(&home).join(".ssh").join(name))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+// ✅ Espone un canale direct-tcpip su una porta locale, così la sessione
+// successiva può usare un vero TcpStream (richiesto da set_tcp_stream)
+fn spawn_jump_forwarder(hop_session: Session, channel: Channel) -> Result<SocketAddr, String> {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .map_err(|e| format!("Errore bind forwarder locale: {}", e))?;
+    let local_addr = listener
+        .local_addr()
+        .map_err(|e| format!("Errore indirizzo forwarder: {}", e))?;
+
+    thread::spawn(move || {
+        let local = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                println!("⚠️ Forwarder jump host: accept fallito: {}", e);
+                return;
+            }
+        };
+
+        pump_channel(&hop_session, channel, local);
+        println!("🔌 Forwarder jump host chiuso");
+    });
+
+    Ok(local_addr)
+}
+
+// Copia bidirezionale socket locale <-> canale SSH in un solo thread:
+// la sessione è in modalità non bloccante per non tenere il lock di libssh2
+fn pump_channel(session: &Session, channel: Channel, local: TcpStream) {
+    session.set_blocking(false);
+    let Ok(mut pipe) = ChannelPipe::new(local, channel) else {
+        return;
+    };
+
+    let mut buf = [0u8; 16 * 1024];
+    loop {
+        let step = pipe.step(&mut buf);
+        if step.finished {
+            break;
+        }
+        if !step.progressed {
+            thread::sleep(Duration::from_millis(2));
+        }
+    }
+
+    pipe.close();
+}
+
+// ✅ Esito di un passo di copia
+pub struct PipeStep {
+    pub progressed: bool,
+    pub finished: bool,
+    pub bytes_up: u64,
+    pub bytes_down: u64,
+}
+
+// ✅ Collegamento non bloccante tra un TcpStream locale e un canale SSH
+// Usato dal forwarder dei jump host e dai tunnel: la sessione del canale
+// deve essere in modalità non bloccante (set_blocking(false))
+pub struct ChannelPipe {
+    local: TcpStream,
+    channel: Channel,
+    upstream: Vec<u8>,
+    downstream: Vec<u8>,
+    local_open: bool,
+    remote_open: bool,
+}
+
+impl ChannelPipe {
+    pub fn new(local: TcpStream, channel: Channel) -> std::io::Result<Self> {
+        local.set_nonblocking(true)?;
+        Ok(Self {
+            local,
+            channel,
+            upstream: Vec::new(),
+            downstream: Vec::new(),
+            local_open: true,
+            remote_open: true,
+        })
+    }
+
+    pub fn step(&mut self, buf: &mut [u8]) -> PipeStep {
+        let mut step = PipeStep {
+            progressed: false,
+            finished: false,
+            bytes_up: 0,
+            bytes_down: 0,
+        };
+
+        if self.local_open && self.upstream.is_empty() {
+            match self.local.read(buf) {
+                Ok(0) => {
+                    self.local_open = false;
+                    let _ = self.channel.send_eof();
+                }
+                Ok(n) => {
+                    self.upstream.extend_from_slice(&buf[..n]);
+                    step.progressed = true;
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(_) => {
+                    step.finished = true;
+                    return step;
+                }
+            }
+        }
+
+        if !self.upstream.is_empty() {
+            match self.channel.write(&self.upstream) {
+                Ok(n) => {
+                    self.upstream.drain(..n);
+                    step.bytes_up = n as u64;
+                    step.progressed = true;
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(_) => {
+                    step.finished = true;
+                    return step;
+                }
+            }
+        }
+
+        if self.remote_open && self.downstream.is_empty() {
+            match self.channel.read(buf) {
+                Ok(0) => {
+                    if self.channel.eof() {
+                        self.remote_open = false;
+                    }
+                }
+                Ok(n) => {
+                    self.downstream.extend_from_slice(&buf[..n]);
+                    step.progressed = true;
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(_) => {
+                    step.finished = true;
+                    return step;
+                }
+            }
+        }
+
+        if !self.downstream.is_empty() {
+            match self.local.write(&self.downstream) {
+                Ok(n) => {
+                    self.downstream.drain(..n);
+                    step.bytes_down = n as u64;
+                    step.progressed = true;
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(_) => {
+                    step.finished = true;
+                    return step;
+                }
+            }
+        }
+
+        step.finished = (!self.remote_open && self.downstream.is_empty())
+            || (!self.local_open && !self.remote_open);
+        step
+    }
+
+    pub fn close(mut self) {
+        let _ = self.channel.close();
+    }
+}
@@ -0,0 +1,261 @@
+// src-tauri/src/systemd.rs
+// Gestione servizi systemd remoti: elenco unit, azioni, journal in tempo reale
+// e servizi "fissati" per server inclusi nel controllo di salute
+
+use serde::Serialize;
+use tauri::{command, AppHandle, Emitter};
+
+use crate::remote_exec::{execute_on_server, shell_quote, spawn_follow, split_output_sections, ExecOptions};
+use crate::ssh_session::find_server;
+use crate::{load_servers, new_id, ping_server, store_servers, PingResult};
+
+const UNITS_SCRIPT: &str = "echo ==UNITS==; systemctl list-units --type=service --all --no-legend --no-pager --plain; \
+echo ==FILES==; systemctl list-unit-files --type=service --no-legend --no-pager";
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemdUnit {
+    pub name: String,
+    pub load_state: String,
+    pub active_state: String,
+    pub sub_state: String,
+    pub unit_file_state: Option<String>,   // enabled / disabled / static / masked
+    pub description: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalLine {
+    pub stream_id: String,
+    pub server_id: String,
+    pub unit: String,
+    pub line: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerHealth {
+    pub server_id: String,
+    pub ping: PingResult,
+    pub services: Vec<SystemdUnit>,
+    pub services_error: Option<String>,
+    pub healthy: bool,
+}
+
+fn validate_unit(unit: &str) -> Result<String, String> {
+    let unit = unit.trim();
+    let valid = !unit.is_empty()
+        && unit
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "@._:-\\".contains(c));
+    if !valid {
+        return Err(format!("Nome unit non valido: {}", unit));
+    }
+    Ok(unit.to_string())
+}
+
+// ✅ COMANDO: Elenco dei servizi con stato active/sub e stato di abilitazione
+#[command]
+pub async fn systemd_list_units(app: AppHandle, server_id: String) -> Result<Vec<SystemdUnit>, String> {
+    let (server, servers) = find_server(&app, &server_id).await?;
+    let output = execute_on_server(&app, server, servers, UNITS_SCRIPT.to_string(), ExecOptions::default()).await?;
+
+    if output.exit_code == Some(127) || output.stderr.contains("systemctl: command not found") {
+        return Err("systemd non disponibile su questo server".to_string());
+    }
+
+    let sections = split_output_sections(&output.stdout);
+    let files = sections.get("FILES").cloned().unwrap_or_default();
+    let units = sections.get("UNITS").cloned().unwrap_or_default();
+    Ok(parse_units(&units, &files))
+}
+
+// ✅ COMANDO: start / stop / restart / reload / enable / disable (con sudo)
+#[command]
+pub async fn systemd_unit_action(
+    app: AppHandle,
+    server_id: String,
+    unit: String,
+    action: String,
+    sudo_password: Option<String>,
+) -> Result<SystemdUnit, String> {
+    let allowed = ["start", "stop", "restart", "reload", "enable", "disable"];
+    if !allowed.contains(&action.as_str()) {
+        return Err(format!("Azione non supportata: {}", action));
+    }
+    let unit = validate_unit(&unit)?;
+
+    let (server, servers) = find_server(&app, &server_id).await?;
+    let options = ExecOptions {
+        timeout_secs: Some(90),
+        sudo: Some(server.ssh_user != "root"),
+        sudo_password,
+        ..Default::default()
+    };
+
+    println!("⚙️ systemctl {} {} su {}", action, unit, server.name);
+    let command = format!("systemctl {} {}", action, shell_quote(&unit));
+    let output = execute_on_server(&app, server.clone(), servers.clone(), command, options).await?;
+    if !output.success() {
+        return Err(format!("systemctl {} {} fallito: {}", action, unit, output.stderr.trim()));
+    }
+
+    let mut states = query_units(&app, &server_id, &[unit.clone()]).await?;
+    states.pop().ok_or_else(|| format!("Stato di {} non disponibile", unit))
+}
+
+// ✅ COMANDO: Segue `journalctl -u` in tempo reale (evento "journal_line")
+#[command]
+pub async fn systemd_follow_journal(
+    app: AppHandle,
+    server_id: String,
+    unit: String,
+    lines: Option<u32>,
+) -> Result<String, String> {
+    let unit = validate_unit(&unit)?;
+    let (server, servers) = find_server(&app, &server_id).await?;
+
+    let command = format!(
+        "journalctl -u {} -f -n {} --no-pager -o short-iso",
+        shell_quote(&unit),
+        lines.unwrap_or(100)
+    );
+    let stream_id = new_id("journal");
+
+    let app_lines = app.clone();
+    let app_end = app.clone();
+    let (line_stream_id, end_stream_id) = (stream_id.clone(), stream_id.clone());
+
+    spawn_follow(
+        server,
+        servers,
+        command,
+        stream_id.clone(),
+        move |_, line| {
+            let _ = app_lines.emit("journal_line", JournalLine {
+                stream_id: line_stream_id.clone(),
+                server_id: server_id.clone(),
+                unit: unit.clone(),
+                line: line.to_string(),
+            });
+        },
+        move |error| {
+            let _ = app_end.emit("journal_end", serde_json::json!({
+                "streamId": end_stream_id,
+                "error": error,
+            }));
+        },
+    );
+
+    Ok(stream_id)
+}
+
+// ✅ COMANDO: Imposta i servizi fissati di un server
+#[command]
+pub async fn set_pinned_services(app: AppHandle, server_id: String, units: Vec<String>) -> Result<Vec<String>, String> {
+    let units = units
+        .iter()
+        .map(|u| validate_unit(u))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut servers = load_servers(app.clone()).await?;
+    let server = servers
+        .iter_mut()
+        .find(|s| s.id == server_id)
+        .ok_or_else(|| format!("Server '{}' non trovato", server_id))?;
+
+    server.pinned_services = units.clone();
+    store_servers(&app, &servers)?;
+    Ok(units)
+}
+
+// ✅ COMANDO: Controllo di salute = ping + stato dei servizi fissati
+#[command]
+pub async fn check_server_health(app: AppHandle, server_id: String) -> Result<ServerHealth, String> {
+    let (server, _) = find_server(&app, &server_id).await?;
+    let ping = ping_server(server.ip.clone(), server.ssh_port).await?;
+
+    let (services, services_error) = if !ping.is_online || server.pinned_services.is_empty() {
+        (Vec::new(), None)
+    } else {
+        match query_units(&app, &server_id, &server.pinned_services).await {
+            Ok(services) => (services, None),
+            Err(e) => (Vec::new(), Some(e)),
+        }
+    };
+
+    let healthy = ping.is_online
+        && services_error.is_none()
+        && services.iter().all(|s| s.active_state == "active");
+
+    Ok(ServerHealth {
+        server_id,
+        ping,
+        services,
+        services_error,
+        healthy,
+    })
+}
+
+// Stato puntuale di alcune unit tramite `systemctl show`
+async fn query_units(app: &AppHandle, server_id: &str, units: &[String]) -> Result<Vec<SystemdUnit>, String> {
+    let (server, servers) = find_server(app, server_id).await?;
+    let names: Vec<String> = units.iter().map(|u| shell_quote(u)).collect();
+    let command = format!(
+        "systemctl show --no-pager -p Id,LoadState,ActiveState,SubState,UnitFileState,Description {}",
+        names.join(" ")
+    );
+
+    let output = execute_on_server(app, server, servers, command, ExecOptions::default()).await?;
+    if !output.success() {
+        return Err(format!("systemctl show fallito: {}", output.stderr.trim()));
+    }
+
+    Ok(parse_show_output(&output.stdout))
+}
+
+// "nginx.service loaded active running A high performance web server"
+fn parse_units(units: &[String], files: &[String]) -> Vec<SystemdUnit> {
+    units
+        .iter()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?.to_string();
+            let load_state = fields.next()?.to_string();
+            let active_state = fields.next()?.to_string();
+            let sub_state = fields.next()?.to_string();
+            let description = fields.collect::<Vec<_>>().join(" ");
+
+            let unit_file_state = files.iter().find_map(|f| {
+                let mut parts = f.split_whitespace();
+                (parts.next()? == name).then(|| parts.next().map(|s| s.to_string())).flatten()
+            });
+
+            Some(SystemdUnit { name, load_state, active_state, sub_state, unit_file_state, description })
+        })
+        .collect()
+}
+
+// Blocchi chiave=valore separati da righe vuote
+fn parse_show_output(output: &str) -> Vec<SystemdUnit> {
+    output
+        .split("\n\n")
+        .filter_map(|block| {
+            let mut unit = SystemdUnit::default();
+            for line in block.lines() {
+                let Some((key, value)) = line.split_once('=') else { continue };
+                let value = value.trim().to_string();
+                match key {
+                    "Id" => unit.name = value,
+                    "LoadState" => unit.load_state = value,
+                    "ActiveState" => unit.active_state = value,
+                    "SubState" => unit.sub_state = value,
+                    "UnitFileState" => unit.unit_file_state = Some(value).filter(|v| !v.is_empty()),
+                    "Description" => unit.description = value,
+                    _ => {}
+                }
+            }
+            (!unit.name.is_empty()).then_some(unit)
+        })
+        .collect()
+}
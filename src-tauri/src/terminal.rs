@@ -4,6 +4,7 @@ use tauri::{command, AppHandle, Manager};
 use tauri::path::BaseDirectory;
 use once_cell::sync::OnceCell;
 use serde::Serialize;  // ✅ AGGIUNTO
+use crate::ssh_session::{find_saved_target, proxy_jump_arg, resolve_jump_chain};
 
 static TERMINAL_PROCESS: OnceCell<Mutex<Option<Child>>> = OnceCell::new();
 
@@ -14,6 +15,9 @@ pub struct TerminalRequest {
     pub ip: String,
     pub ssh_port: u16,
     pub password: Option<String>,
+    // 🆕 Server salvato da cui prendere la catena di jump host (ProxyJump)
+    // Senza id il server viene cercato per ip/porta/utente
+    pub server_id: Option<String>,
 }
 
 // ✅ AGGIUNTO - Struct per le risposte
@@ -54,20 +58,48 @@ pub async fn open_terminal(app: AppHandle, request: TerminalRequest) -> Result<T
         return Err(format!("Binario ttyd non trovato in: {}", ttyd_path.display()));
     }
 
+    // 🆕 Jump host / bastion
+    let saved = find_saved_target(
+        &app,
+        request.server_id.as_deref(),
+        &request.ip,
+        request.ssh_port,
+        &request.ssh_user,
+    )
+    .await?;
+    let chain = match &saved {
+        Some((server, servers)) => resolve_jump_chain(server, servers)?,
+        None => Vec::new(),
+    };
+
+    // sshpass risponde a un solo prompt: con `-J` la password di un bastion resterebbe in attesa
+    if request.password.is_some() && !cfg!(target_os = "windows") {
+        if let Some(hop) = chain.iter().find(|hop| hop.auth_method == "password") {
+            return Err(format!(
+                "Il jump host '{}' usa l'autenticazione con password, non supportata dal terminale con ProxyJump: configura una chiave SSH sul bastion",
+                hop.name
+            ));
+        }
+    }
+
+    let jump_arg = proxy_jump_arg(&chain)
+        .map(|jump| format!("-J {} ", jump))
+        .unwrap_or_default();
+
     let ssh_command = if cfg!(target_os = "windows") {
         format!(
-            "ssh -p {} {}@{}",
-            request.ssh_port, request.ssh_user, request.ip
+            "ssh {}-p {} {}@{}",
+            jump_arg, request.ssh_port, request.ssh_user, request.ip
         )
     } else if let Some(password) = request.password.clone() {
         format!(
-            "sshpass -p '{}' ssh -tt -o StrictHostKeyChecking=no -p {} {}@{}",
-            password, request.ssh_port, request.ssh_user, request.ip
+            "sshpass -p '{}' ssh -tt -o StrictHostKeyChecking=no {}-p {} {}@{}",
+            password, jump_arg, request.ssh_port, request.ssh_user, request.ip
         )
     } else {
         format!(
-            "ssh -tt -o StrictHostKeyChecking=no -p {} {}@{}",
-            request.ssh_port, request.ssh_user, request.ip
+            "ssh -tt -o StrictHostKeyChecking=no {}-p {} {}@{}",
+            jump_arg, request.ssh_port, request.ssh_user, request.ip
         )
     };
 
@@ -0,0 +1,576 @@
+// src-tauri/src/tunnels.rs
+// Port forwarding locale (-L), remoto (-R) e dinamico/SOCKS (-D)
+// I tunnel usano direttamente le sessioni ssh2, senza processi `ssh` esterni
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use ssh2::{Channel, ErrorCode, Listener, Session};
+use tauri::{command, AppHandle, Emitter};
+
+use crate::ssh_session::{find_server, open_session, ChannelPipe};
+use crate::{load_servers, store_servers, Server};
+
+static TUNNELS: OnceCell<Mutex<HashMap<String, TunnelHandle>>> = OnceCell::new();
+
+const KEEPALIVE_INTERVAL_SECS: u32 = 30;
+const MAX_RESTART_DELAY_SECS: u64 = 30;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PortForward {
+    pub id: String,
+    pub kind: String,                 // "local" | "remote" | "dynamic"
+    pub bind_host: Option<String>,    // default 127.0.0.1 (local/dynamic) o tutte le interfacce remote
+    pub bind_port: u16,
+    pub target_host: Option<String>,  // non usato per "dynamic"
+    pub target_port: Option<u16>,
+    #[serde(default)]
+    pub auto_restart: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelInfo {
+    pub tunnel_id: String,
+    pub server_id: String,
+    pub forward: PortForward,
+    pub status: String,               // "connecting" | "active" | "restarting" | "error" | "stopped"
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub active_connections: u32,
+    pub restarts: u32,
+    pub started_at: String,
+    pub last_error: Option<String>,
+}
+
+struct TunnelStats {
+    status: Mutex<String>,
+    last_error: Mutex<Option<String>>,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    active_connections: AtomicU32,
+    restarts: AtomicU32,
+}
+
+struct TunnelHandle {
+    server_id: String,
+    forward: PortForward,
+    started_at: String,
+    stop: Arc<AtomicBool>,
+    stats: Arc<TunnelStats>,
+}
+
+impl TunnelHandle {
+    fn info(&self, tunnel_id: &str) -> TunnelInfo {
+        TunnelInfo {
+            tunnel_id: tunnel_id.to_string(),
+            server_id: self.server_id.clone(),
+            forward: self.forward.clone(),
+            status: self.stats.status.lock().unwrap().clone(),
+            bytes_sent: self.stats.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: self.stats.bytes_received.load(Ordering::Relaxed),
+            active_connections: self.stats.active_connections.load(Ordering::Relaxed),
+            restarts: self.stats.restarts.load(Ordering::Relaxed),
+            started_at: self.started_at.clone(),
+            last_error: self.stats.last_error.lock().unwrap().clone(),
+        }
+    }
+}
+
+fn tunnel_id(server_id: &str, forward_id: &str) -> String {
+    format!("{}:{}", server_id, forward_id)
+}
+
+fn registry() -> &'static Mutex<HashMap<String, TunnelHandle>> {
+    TUNNELS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub(crate) fn validate_forward(forward: &PortForward) -> Result<(), String> {
+    if forward.id.trim().is_empty() {
+        return Err("Id del port forward mancante".to_string());
+    }
+
+    match forward.kind.as_str() {
+        "local" | "remote" => {
+            if forward.target_host.as_deref().map_or(true, |h| h.trim().is_empty()) {
+                return Err("Host di destinazione obbligatorio per forward locale/remoto".to_string());
+            }
+            if forward.target_port.unwrap_or(0) == 0 {
+                return Err("Porta di destinazione non valida".to_string());
+            }
+        }
+        "dynamic" => {}
+        other => return Err(format!("Tipo di forward non supportato: {}", other)),
+    }
+
+    if forward.bind_port == 0 && forward.kind != "remote" {
+        return Err("Porta di ascolto non valida".to_string());
+    }
+
+    Ok(())
+}
+
+// ✅ COMANDO: Crea o aggiorna un port forward salvato sul server
+#[command]
+pub async fn save_port_forward(
+    app: AppHandle,
+    server_id: String,
+    forward: PortForward,
+) -> Result<Vec<PortForward>, String> {
+    validate_forward(&forward)?;
+
+    let mut servers = load_servers(app.clone()).await?;
+    let server = servers
+        .iter_mut()
+        .find(|s| s.id == server_id)
+        .ok_or_else(|| format!("Server '{}' non trovato", server_id))?;
+
+    match server.port_forwards.iter_mut().find(|f| f.id == forward.id) {
+        Some(existing) => *existing = forward,
+        None => server.port_forwards.push(forward),
+    }
+
+    let forwards = server.port_forwards.clone();
+    store_servers(&app, &servers)?;
+    Ok(forwards)
+}
+
+// ✅ COMANDO: Elimina un port forward (ferma il tunnel se attivo)
+#[command]
+pub async fn delete_port_forward(
+    app: AppHandle,
+    server_id: String,
+    forward_id: String,
+) -> Result<Vec<PortForward>, String> {
+    let _ = stop_tunnel(tunnel_id(&server_id, &forward_id));
+
+    let mut servers = load_servers(app.clone()).await?;
+    let server = servers
+        .iter_mut()
+        .find(|s| s.id == server_id)
+        .ok_or_else(|| format!("Server '{}' non trovato", server_id))?;
+
+    server.port_forwards.retain(|f| f.id != forward_id);
+
+    let forwards = server.port_forwards.clone();
+    store_servers(&app, &servers)?;
+    Ok(forwards)
+}
+
+// ✅ COMANDO: Avvia un tunnel (indipendente dal terminale)
+#[command]
+pub async fn start_tunnel(
+    app: AppHandle,
+    server_id: String,
+    forward_id: String,
+) -> Result<TunnelInfo, String> {
+    let (server, servers) = find_server(&app, &server_id).await?;
+    let forward = server
+        .port_forwards
+        .iter()
+        .find(|f| f.id == forward_id)
+        .cloned()
+        .ok_or_else(|| format!("Port forward '{}' non trovato", forward_id))?;
+    validate_forward(&forward)?;
+
+    let id = tunnel_id(&server_id, &forward_id);
+    let mut tunnels = registry().lock().unwrap();
+
+    // Un tunnel terminato resta nel registro solo per le statistiche
+    if let Some(existing) = tunnels.get(&id) {
+        if !existing.stop.load(Ordering::Relaxed) {
+            return Ok(existing.info(&id));
+        }
+    }
+
+    let handle = TunnelHandle {
+        server_id: server_id.clone(),
+        forward: forward.clone(),
+        started_at: chrono::Local::now().to_rfc3339(),
+        stop: Arc::new(AtomicBool::new(false)),
+        stats: Arc::new(TunnelStats {
+            status: Mutex::new("connecting".to_string()),
+            last_error: Mutex::new(None),
+            bytes_sent: AtomicU64::new(0),
+            bytes_received: AtomicU64::new(0),
+            active_connections: AtomicU32::new(0),
+            restarts: AtomicU32::new(0),
+        }),
+    };
+
+    let info = handle.info(&id);
+    let stop = handle.stop.clone();
+    let stats = handle.stats.clone();
+    tunnels.insert(id.clone(), handle);
+    drop(tunnels);
+
+    println!("🚇 Avvio tunnel {} ({} {})", id, forward.kind, forward.bind_port);
+
+    thread::spawn(move || {
+        run_tunnel(&app, &id, &server, &servers, &forward, &stop, &stats);
+        stop.store(true, Ordering::Relaxed);
+    });
+
+    Ok(info)
+}
+
+// ✅ COMANDO: Ferma un tunnel attivo
+#[command]
+pub fn stop_tunnel(tunnel_id: String) -> Result<(), String> {
+    let mut tunnels = registry().lock().unwrap();
+    match tunnels.remove(&tunnel_id) {
+        Some(handle) => {
+            handle.stop.store(true, Ordering::Relaxed);
+            println!("🛑 Tunnel {} fermato", tunnel_id);
+            Ok(())
+        }
+        None => Err(format!("Tunnel '{}' non attivo", tunnel_id)),
+    }
+}
+
+// ✅ COMANDO: Elenco dei tunnel con contatori di traffico
+#[command]
+pub fn list_tunnels() -> Vec<TunnelInfo> {
+    let tunnels = registry().lock().unwrap();
+    let mut list: Vec<TunnelInfo> = tunnels.iter().map(|(id, handle)| handle.info(id)).collect();
+    list.sort_by(|a, b| a.tunnel_id.cmp(&b.tunnel_id));
+    list
+}
+
+// 🆕 Ferma tutti i tunnel (chiusura app)
+pub fn stop_all_tunnels() {
+    if let Some(lock) = TUNNELS.get() {
+        for (_, handle) in lock.lock().unwrap().drain() {
+            handle.stop.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+fn set_status(app: &AppHandle, id: &str, stats: &TunnelStats, status: &str) {
+    *stats.status.lock().unwrap() = status.to_string();
+
+    if let Some(lock) = TUNNELS.get() {
+        if let Some(handle) = lock.lock().unwrap().get(id) {
+            let _ = app.emit("tunnel_status", handle.info(id));
+        }
+    }
+}
+
+// Ciclo di vita del tunnel: connessione, servizio e riavvio automatico
+fn run_tunnel(
+    app: &AppHandle,
+    id: &str,
+    server: &Server,
+    servers: &[Server],
+    forward: &PortForward,
+    stop: &AtomicBool,
+    stats: &TunnelStats,
+) {
+    let mut delay_secs = 1;
+
+    while !stop.load(Ordering::Relaxed) {
+        set_status(app, id, stats, "connecting");
+
+        let result = open_session(server, servers)
+            .and_then(|session| serve_forward(app, id, &session, forward, stop, stats));
+        stats.active_connections.store(0, Ordering::Relaxed);
+
+        let error = match result {
+            Ok(()) => break,
+            Err(e) => e,
+        };
+
+        println!("⚠️ Tunnel {}: {}", id, error);
+        *stats.last_error.lock().unwrap() = Some(error);
+
+        if !forward.auto_restart || stop.load(Ordering::Relaxed) {
+            set_status(app, id, stats, "error");
+            return;
+        }
+
+        stats.restarts.fetch_add(1, Ordering::Relaxed);
+        set_status(app, id, stats, "restarting");
+
+        let resume_at = Instant::now() + Duration::from_secs(delay_secs);
+        while Instant::now() < resume_at && !stop.load(Ordering::Relaxed) {
+            thread::sleep(Duration::from_millis(200));
+        }
+        delay_secs = (delay_secs * 2).min(MAX_RESTART_DELAY_SECS);
+    }
+
+    set_status(app, id, stats, "stopped");
+}
+
+enum ForwardSource {
+    Local(TcpListener),
+    Remote(Listener),
+}
+
+// Connessione locale con destinazione nota, in attesa del canale direct-tcpip
+struct PendingConnection {
+    stream: TcpStream,
+    peer: SocketAddr,
+    host: String,
+    port: u16,
+    socks: bool,                      // risposta SOCKS5 da inviare all'apertura del canale
+}
+
+// Esegue una chiamata ssh2 in modalità bloccante (apertura canali, listen remoto)
+fn blocking_call<T>(session: &Session, f: impl FnOnce() -> Result<T, ssh2::Error>) -> Result<T, ssh2::Error> {
+    session.set_blocking(true);
+    session.set_timeout(10_000);
+    let result = f();
+    session.set_timeout(0);
+    session.set_blocking(false);
+    result
+}
+
+// In modalità non bloccante libssh2 ritorna EAGAIN (-37) quando non c'è nulla da fare
+fn would_block(e: ssh2::Error) -> Result<(), ssh2::Error> {
+    if e.code() == ErrorCode::Session(-37) {
+        Ok(())
+    } else {
+        Err(e)
+    }
+}
+
+fn serve_forward(
+    app: &AppHandle,
+    id: &str,
+    session: &Session,
+    forward: &PortForward,
+    stop: &AtomicBool,
+    stats: &TunnelStats,
+) -> Result<(), String> {
+    session.set_keepalive(true, KEEPALIVE_INTERVAL_SECS);
+
+    let mut source = match forward.kind.as_str() {
+        "remote" => {
+            let bind_host = forward.bind_host.as_deref();
+            let (listener, bound_port) = blocking_call(session, || {
+                session.channel_forward_listen(forward.bind_port, bind_host, None)
+            })
+            .map_err(|e| format!("Forward remoto sulla porta {} rifiutato: {}", forward.bind_port, e))?;
+            println!("🚇 Forward remoto in ascolto sulla porta {}", bound_port);
+            ForwardSource::Remote(listener)
+        }
+        _ => {
+            let bind_host = forward.bind_host.as_deref().unwrap_or("127.0.0.1");
+            let listener = TcpListener::bind((bind_host, forward.bind_port))
+                .map_err(|e| format!("Impossibile ascoltare su {}:{}: {}", bind_host, forward.bind_port, e))?;
+            listener
+                .set_nonblocking(true)
+                .map_err(|e| format!("Errore listener locale: {}", e))?;
+            ForwardSource::Local(listener)
+        }
+    };
+
+    session.set_blocking(false);
+    *stats.last_error.lock().unwrap() = None;
+    set_status(app, id, stats, "active");
+
+    let mut pipes: Vec<ChannelPipe> = Vec::new();
+    let mut buf = [0u8; 16 * 1024];
+    let mut next_keepalive = Instant::now();
+
+    // L'handshake SOCKS avviene in un thread per connessione: il pump riceve solo connessioni pronte
+    let (ready_tx, ready_rx) = mpsc::channel::<PendingConnection>();
+    let mut pending: VecDeque<PendingConnection> = VecDeque::new();
+
+    while !stop.load(Ordering::Relaxed) {
+        let mut progressed = false;
+
+        match &mut source {
+            ForwardSource::Local(listener) => match listener.accept() {
+                Ok((stream, peer)) => {
+                    progressed = true;
+                    accept_local_connection(id, forward, stream, peer, &ready_tx);
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) => return Err(format!("Errore accept locale: {}", e)),
+            },
+            ForwardSource::Remote(listener) => match listener.accept() {
+                Ok(channel) => {
+                    progressed = true;
+                    match open_remote_connection(forward, channel) {
+                        Ok(pipe) => pipes.push(pipe),
+                        Err(e) => println!("⚠️ Tunnel {}: {}", id, e),
+                    }
+                }
+                Err(e) => would_block(e).map_err(|e| format!("Errore accept remoto: {}", e))?,
+            },
+        }
+
+        while let Ok(connection) = ready_rx.try_recv() {
+            pending.push_back(connection);
+        }
+
+        // libssh2 apre un solo canale direct-tcpip alla volta: si riprova la prima connessione finché non è pronta
+        if let Some(connection) = pending.front() {
+            match session.channel_direct_tcpip(&connection.host, connection.port, None) {
+                Err(e) if e.code() == ErrorCode::Session(-37) => {}
+                channel => {
+                    progressed = true;
+                    let connection = pending.pop_front().unwrap();
+                    let peer = connection.peer;
+                    match finish_local_connection(connection, channel) {
+                        Ok(pipe) => pipes.push(pipe),
+                        Err(e) => println!("⚠️ Tunnel {}: connessione da {} rifiutata: {}", id, peer, e),
+                    }
+                }
+            }
+        }
+
+        let mut i = 0;
+        while i < pipes.len() {
+            let step = pipes[i].step(&mut buf);
+            stats.bytes_sent.fetch_add(step.bytes_up, Ordering::Relaxed);
+            stats.bytes_received.fetch_add(step.bytes_down, Ordering::Relaxed);
+            progressed |= step.progressed;
+
+            if step.finished {
+                pipes.swap_remove(i).close();
+            } else {
+                i += 1;
+            }
+        }
+        stats.active_connections.store(pipes.len() as u32, Ordering::Relaxed);
+
+        if Instant::now() >= next_keepalive {
+            match session.keepalive_send() {
+                Ok(secs) => next_keepalive = Instant::now() + Duration::from_secs(secs.max(1) as u64),
+                Err(e) => would_block(e).map_err(|e| format!("Connessione SSH persa: {}", e))?,
+            }
+        }
+
+        if !progressed {
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    for pipe in pipes {
+        pipe.close();
+    }
+    Ok(())
+}
+
+// -L e -D: la destinazione è nota subito (-L) o dopo l'handshake SOCKS5 (-D, in un thread dedicato)
+fn accept_local_connection(id: &str, forward: &PortForward, stream: TcpStream, peer: SocketAddr, ready: &Sender<PendingConnection>) {
+    if forward.kind != "dynamic" {
+        let _ = ready.send(PendingConnection {
+            stream,
+            peer,
+            host: forward.target_host.clone().unwrap_or_default(),
+            port: forward.target_port.unwrap_or(0),
+            socks: false,
+        });
+        return;
+    }
+
+    let id = id.to_string();
+    let ready = ready.clone();
+    thread::spawn(move || {
+        let mut stream = stream;
+        let result = stream
+            .set_nonblocking(false)
+            .and_then(|_| stream.set_read_timeout(Some(Duration::from_secs(5))))
+            .map_err(|e| e.to_string())
+            .and_then(|_| socks5_handshake(&mut stream));
+
+        match result {
+            Ok((host, port)) => {
+                let _ = ready.send(PendingConnection { stream, peer, host, port, socks: true });
+            }
+            Err(e) => println!("⚠️ Tunnel {}: connessione da {} rifiutata: {}", id, peer, e),
+        }
+    });
+}
+
+// Canale direct-tcpip aperto (o rifiutato): risposta SOCKS e collegamento al pump
+fn finish_local_connection(connection: PendingConnection, channel: Result<Channel, ssh2::Error>) -> Result<ChannelPipe, String> {
+    let PendingConnection { mut stream, host, port, socks, .. } = connection;
+
+    if socks {
+        let reply_code = if channel.is_ok() { 0x00 } else { 0x05 };
+        let _ = stream.write_all(&[0x05, reply_code, 0x00, 0x01, 0, 0, 0, 0, 0, 0]);
+        let _ = stream.set_read_timeout(None);
+    }
+
+    let channel = channel.map_err(|e| format!("Canale verso {}:{} fallito: {}", host, port, e))?;
+    ChannelPipe::new(stream, channel).map_err(|e| e.to_string())
+}
+
+// -R: connessione arrivata sul server, inoltrata verso la destinazione locale
+fn open_remote_connection(forward: &PortForward, channel: Channel) -> Result<ChannelPipe, String> {
+    let host = forward.target_host.clone().unwrap_or_default();
+    let port = forward.target_port.unwrap_or(0);
+
+    let addr = (host.as_str(), port)
+        .to_socket_addrs()
+        .map_err(|e| format!("Destinazione {}:{} non valida: {}", host, port, e))?
+        .next()
+        .ok_or_else(|| format!("Destinazione {} non risolta", host))?;
+
+    let stream = TcpStream::connect_timeout(&addr, Duration::from_secs(5))
+        .map_err(|e| format!("Destinazione {}:{} non raggiungibile: {}", host, port, e))?;
+
+    ChannelPipe::new(stream, channel).map_err(|e| e.to_string())
+}
+
+// SOCKS5 minimale (solo CONNECT, nessuna autenticazione)
+fn socks5_handshake(stream: &mut TcpStream) -> Result<(String, u16), String> {
+    let mut header = [0u8; 2];
+    stream.read_exact(&mut header).map_err(|e| format!("SOCKS: {}", e))?;
+    if header[0] != 0x05 {
+        return Err("SOCKS: versione non supportata (solo SOCKS5)".to_string());
+    }
+
+    let mut methods = vec![0u8; header[1] as usize];
+    stream.read_exact(&mut methods).map_err(|e| format!("SOCKS: {}", e))?;
+    if !methods.contains(&0x00) {
+        let _ = stream.write_all(&[0x05, 0xFF]);
+        return Err("SOCKS: nessun metodo di autenticazione compatibile".to_string());
+    }
+    stream.write_all(&[0x05, 0x00]).map_err(|e| format!("SOCKS: {}", e))?;
+
+    let mut request = [0u8; 4];
+    stream.read_exact(&mut request).map_err(|e| format!("SOCKS: {}", e))?;
+    if request[1] != 0x01 {
+        let _ = stream.write_all(&[0x05, 0x07, 0x00, 0x01, 0, 0, 0, 0, 0, 0]);
+        return Err("SOCKS: solo il comando CONNECT è supportato".to_string());
+    }
+
+    let host = match request[3] {
+        0x01 => {
+            let mut ip = [0u8; 4];
+            stream.read_exact(&mut ip).map_err(|e| format!("SOCKS: {}", e))?;
+            std::net::Ipv4Addr::from(ip).to_string()
+        }
+        0x03 => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len).map_err(|e| format!("SOCKS: {}", e))?;
+            let mut name = vec![0u8; len[0] as usize];
+            stream.read_exact(&mut name).map_err(|e| format!("SOCKS: {}", e))?;
+            String::from_utf8_lossy(&name).to_string()
+        }
+        0x04 => {
+            let mut ip = [0u8; 16];
+            stream.read_exact(&mut ip).map_err(|e| format!("SOCKS: {}", e))?;
+            std::net::Ipv6Addr::from(ip).to_string()
+        }
+        other => return Err(format!("SOCKS: tipo indirizzo {} non supportato", other)),
+    };
+
+    let mut port = [0u8; 2];
+    stream.read_exact(&mut port).map_err(|e| format!("SOCKS: {}", e))?;
+
+    Ok((host, u16::from_be_bytes(port)))
+}
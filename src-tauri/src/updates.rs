@@ -0,0 +1,227 @@
+// src-tauri/src/updates.rs
+// Stato degli aggiornamenti dei pacchetti (apt, dnf/yum, pacman, apk, brew)
+// e azione di aggiornamento con output in streaming e rilevamento del riavvio richiesto
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle};
+
+use crate::remote_exec::{execute_on_server, split_output_sections, ExecOptions, RemoteCommandResult};
+use crate::ssh_session::find_server;
+use crate::{new_id, read_json_file, write_json_file};
+
+const UPDATES_FILE: &str = "update-status.json";
+
+const DETECT_PM: &str = r#"PM=""
+for pm in apt-get dnf yum pacman apk brew; do
+  if command -v $pm >/dev/null 2>&1; then PM=$pm; break; fi
+done"#;
+
+const REBOOT_CHECK: &str = r#"echo ==REBOOT==
+if [ -f /var/run/reboot-required ]; then echo yes
+elif command -v needs-restarting >/dev/null 2>&1; then needs-restarting -r >/dev/null 2>&1 || echo yes
+fi"#;
+
+const PROBE_SCRIPT: &str = r#"echo ==PM==; echo $PM
+echo ==UPDATES==
+case $PM in
+  apt-get) apt list --upgradable 2>/dev/null | grep -v '^Listing' | awk -F/ '{print $1}' ;;
+  dnf|yum) $PM -q check-update 2>/dev/null | awk 'NF==3 && $1 ~ /\./ {print $1}' ;;
+  pacman) (checkupdates 2>/dev/null || pacman -Qu 2>/dev/null) | awk '{print $1}' ;;
+  apk) apk list -u 2>/dev/null | awk '{print $1}' ;;
+  brew) brew outdated --quiet 2>/dev/null ;;
+esac
+echo ==SECURITY==
+case $PM in
+  apt-get) apt list --upgradable 2>/dev/null | grep -- '-security' | awk -F/ '{print $1}' ;;
+  dnf) dnf -q updateinfo list --security 2>/dev/null | awk 'NF>=3 {print $3}' ;;
+  yum) yum -q updateinfo list security 2>/dev/null | awk 'NF>=3 {print $3}' ;;
+esac"#;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageUpdateStatus {
+    pub server_id: String,
+    pub checked_at: String,
+    pub package_manager: Option<String>,
+    pub pending_count: usize,
+    pub security_count: Option<usize>,   // None se il gestore non distingue gli aggiornamenti di sicurezza
+    pub packages: Vec<String>,
+    pub reboot_required: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyUpdatesResult {
+    pub result: RemoteCommandResult,
+    pub reboot_required: bool,
+}
+
+// ✅ COMANDO: Rileva il gestore pacchetti e conta gli aggiornamenti in sospeso
+// Con refresh=true aggiorna prima l'indice dei pacchetti (richiede sudo)
+#[command]
+pub async fn check_package_updates(
+    app: AppHandle,
+    server_id: String,
+    refresh: Option<bool>,
+    sudo_password: Option<String>,
+) -> Result<PackageUpdateStatus, String> {
+    let (server, servers) = find_server(&app, &server_id).await?;
+
+    if refresh.unwrap_or(false) {
+        let refresh_script = format!(
+            "{}\ncase $PM in\n  apt-get) apt-get update -qq ;;\n  dnf|yum) $PM -q makecache ;;\n  pacman) pacman -Sy ;;\n  apk) apk update -q ;;\nesac",
+            DETECT_PM
+        );
+        let options = ExecOptions {
+            timeout_secs: Some(300),
+            sudo: Some(server.ssh_user != "root"),
+            sudo_password,
+            ..Default::default()
+        };
+        let output = execute_on_server(&app, server.clone(), servers.clone(), refresh_script, options).await?;
+        if !output.success() {
+            println!("⚠️ Refresh indice pacchetti fallito su {}: {}", server.name, output.stderr.trim());
+        }
+    }
+
+    let script = format!("{}\n{}\n{}", DETECT_PM, PROBE_SCRIPT, REBOOT_CHECK);
+    let options = ExecOptions {
+        timeout_secs: Some(180),
+        ..Default::default()
+    };
+    let output = execute_on_server(&app, server.clone(), servers, script, options).await?;
+    if output.timed_out {
+        return Err("Timeout durante il controllo aggiornamenti".to_string());
+    }
+
+    let mut status = parse_update_probe(&output.stdout);
+    status.server_id = server_id.clone();
+    status.checked_at = chrono::Local::now().to_rfc3339();
+    if status.package_manager.is_none() {
+        return Err(format!("Nessun gestore pacchetti supportato trovato su {}", server.name));
+    }
+
+    println!(
+        "📦 {}: {} aggiornamenti ({} di sicurezza) via {}",
+        server.name,
+        status.pending_count,
+        status.security_count.map_or("?".to_string(), |c| c.to_string()),
+        status.package_manager.as_deref().unwrap_or("?")
+    );
+
+    let mut statuses: HashMap<String, PackageUpdateStatus> = read_json_file(&app, UPDATES_FILE)?;
+    statuses.insert(server_id, status.clone());
+    write_json_file(&app, UPDATES_FILE, &statuses)?;
+
+    Ok(status)
+}
+
+// ✅ COMANDO: Ultimo stato aggiornamenti noto per tutti i server
+#[command]
+pub async fn get_package_update_status(app: AppHandle) -> Result<HashMap<String, PackageUpdateStatus>, String> {
+    read_json_file(&app, UPDATES_FILE)
+}
+
+// ✅ COMANDO: Applica gli aggiornamenti con output in streaming ("remote_command_output")
+#[command]
+pub async fn apply_package_updates(
+    app: AppHandle,
+    server_id: String,
+    security_only: Option<bool>,
+    sudo_password: Option<String>,
+    execution_id: Option<String>,
+) -> Result<ApplyUpdatesResult, String> {
+    let (server, servers) = find_server(&app, &server_id).await?;
+    let security_only = security_only.unwrap_or(false);
+
+    // Il gestore serve prima dello script: brew rifiuta di girare come root (niente sudo su macOS)
+    // e solo apt, dnf e yum sanno limitarsi agli aggiornamenti di sicurezza
+    let probe = execute_on_server(&app, server.clone(), servers.clone(), format!("{}\necho $PM", DETECT_PM), ExecOptions::default()).await?;
+    let pm = probe.stdout.trim().to_string();
+    let is_brew = pm == "brew";
+    if security_only && !matches!(pm.as_str(), "apt-get" | "dnf" | "yum") {
+        return Err(format!(
+            "{} non distingue gli aggiornamenti di sicurezza: esegui l'aggiornamento completo",
+            if pm.is_empty() { "Il gestore pacchetti" } else { pm.as_str() }
+        ));
+    }
+
+    // apt: solo i pacchetti in arrivo dall'archivio -security (gli stessi contati dalla sonda)
+    let (apt_upgrade, dnf_security) = if security_only {
+        (
+            r#"PKGS=$(apt list --upgradable 2>/dev/null | grep -- '-security' | awk -F/ '{print $1}'); if [ -n "$PKGS" ]; then apt-get -y -q install --only-upgrade $PKGS; else echo "Nessun aggiornamento di sicurezza"; fi"#,
+            " --security",
+        )
+    } else {
+        ("apt-get -y -q upgrade", "")
+    };
+    let apply = format!(
+        r#"case $PM in
+  apt-get) export DEBIAN_FRONTEND=noninteractive; apt-get update -q && {0} ;;
+  dnf) dnf -y upgrade{1} ;;
+  yum) yum -y update{1} ;;
+  pacman) pacman -Syu --noconfirm ;;
+  apk) apk update && apk upgrade ;;
+  brew) brew upgrade ;;
+  *) echo "Nessun gestore pacchetti supportato" >&2; exit 127 ;;
+esac
+STATUS=$?
+{2}
+exit $STATUS"#,
+        apt_upgrade, dnf_security, REBOOT_CHECK
+    );
+    let script = format!("{}\n{}", DETECT_PM, apply);
+
+    let options = ExecOptions {
+        timeout_secs: Some(3600),
+        sudo: Some(server.ssh_user != "root" && !is_brew),
+        sudo_password,
+        stream: Some(true),
+        execution_id: Some(execution_id.unwrap_or_else(|| new_id("updates"))),
+        // Un aggiornamento bloccato non deve restare in esecuzione dopo il timeout
+        kill_on_timeout: Some(true),
+    };
+
+    println!("⬆️ Aggiornamento pacchetti su {} (solo sicurezza: {})", server.name, security_only);
+    let mut result = execute_on_server(&app, server, servers, script, options).await?;
+
+    // La sezione di riavvio è in coda all'output: la si rimuove da quanto mostrato all'utente
+    let sections = split_output_sections(&result.stdout);
+    let reboot_required = sections.get("REBOOT").map_or(false, |l| l.iter().any(|v| v.trim() == "yes"));
+    if let Some(pos) = result.stdout.rfind("==REBOOT==") {
+        result.stdout.truncate(pos);
+    }
+
+    Ok(ApplyUpdatesResult { result, reboot_required })
+}
+
+// ✅ Parsing dell'output della sonda
+pub fn parse_update_probe(output: &str) -> PackageUpdateStatus {
+    let sections = split_output_sections(output);
+    let section = |name: &str| -> Vec<String> {
+        sections
+            .get(name)
+            .map(|lines| lines.iter().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect())
+            .unwrap_or_default()
+    };
+
+    let package_manager = section("PM").into_iter().next().map(|pm| match pm.as_str() {
+        "apt-get" => "apt".to_string(),
+        other => other.to_string(),
+    });
+
+    let packages = section("UPDATES");
+    let security = section("SECURITY");
+    // Gli stessi gestori per cui apply_package_updates supporta security_only
+    let distinguishes_security = matches!(package_manager.as_deref(), Some("apt" | "dnf" | "yum"));
+
+    PackageUpdateStatus {
+        pending_count: packages.len(),
+        security_count: distinguishes_security.then_some(security.len()),
+        packages,
+        reboot_required: section("REBOOT").iter().any(|l| l == "yes"),
+        package_manager,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,193 @@
+// src-tauri/src/validation.rs
+// Validazione dei record Server: host, porta, utente, coerenza del metodo di autenticazione, MAC
+// Usata da save_server e da build_preview per tutti gli import (errori = record scartato, warning = record accettato)
+
+use std::net::IpAddr;
+use std::path::Path;
+use serde::Serialize;
+use tauri::command;
+
+use crate::tunnels::validate_forward;
+use crate::Server;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordValidation {
+    pub index: usize,                    // posizione nel file / nella lista importata
+    pub server_id: Option<String>,
+    pub name: Option<String>,
+    pub errors: Vec<ValidationIssue>,
+    pub warnings: Vec<ValidationIssue>,
+}
+
+impl RecordValidation {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    fn error(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.push(ValidationIssue { field: field.to_string(), message: message.into() });
+    }
+
+    fn warning(&mut self, field: &str, message: impl Into<String>) {
+        self.warnings.push(ValidationIssue { field: field.to_string(), message: message.into() });
+    }
+
+    // Riepilogo leggibile per i comandi che ritornano String come errore
+    pub fn summary(&self) -> String {
+        self.errors
+            .iter()
+            .map(|e| format!("{}: {}", e.field, e.message))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+// Hostname RFC 1123: etichette alfanumeriche con '-', max 63 caratteri, totale max 253
+// '_' è ammesso: fuori dallo standard ma comune in /etc/hosts, alias ssh e DNS interni (build_box)
+pub fn is_valid_hostname(host: &str) -> bool {
+    let host = host.strip_suffix('.').unwrap_or(host);
+    !host.is_empty()
+        && host.len() <= 253
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+pub fn is_valid_host(host: &str) -> bool {
+    let host = host.trim();
+    let bracketless = host.strip_prefix('[').and_then(|h| h.strip_suffix(']')).unwrap_or(host);
+    bracketless.parse::<IpAddr>().is_ok() || is_valid_hostname(host)
+}
+
+// AA:BB:CC:DD:EE:FF oppure AA-BB-CC-DD-EE-FF (come il form del frontend)
+pub fn is_valid_mac(mac: &str) -> bool {
+    let mac = mac.trim();
+    let separator = if mac.contains('-') { '-' } else { ':' };
+    let parts: Vec<&str> = mac.split(separator).collect();
+    parts.len() == 6 && parts.iter().all(|p| p.len() == 2 && p.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+// ✅ Validazione di un singolo record
+pub fn validate_server(server: &Server) -> RecordValidation {
+    let mut result = RecordValidation {
+        server_id: Some(server.id.clone()).filter(|id| !id.is_empty()),
+        name: Some(server.name.clone()).filter(|n| !n.is_empty()),
+        ..Default::default()
+    };
+
+    if server.name.trim().is_empty() {
+        result.error("name", "Nome obbligatorio");
+    }
+
+    if server.ip.trim().is_empty() {
+        result.error("ip", "Host obbligatorio");
+    } else if !is_valid_host(&server.ip) {
+        result.error("ip", format!("'{}' non è un indirizzo IP o un hostname valido", server.ip));
+    } else if server.ip.contains('_') {
+        result.warning("ip", format!("'{}' contiene '_': valido solo per risolutori che lo accettano (hosts, DNS interni)", server.ip));
+    }
+
+    if server.ssh_port == 0 {
+        result.error("sshPort", "La porta deve essere compresa tra 1 e 65535");
+    }
+
+    if server.ssh_user.trim().is_empty() {
+        result.error("sshUser", "Utente SSH obbligatorio");
+    } else if server.ssh_user.contains(|c: char| c.is_whitespace() || c == '@') {
+        result.error("sshUser", "L'utente SSH non può contenere spazi o '@'");
+    }
+
+    let key_path = server.ssh_key_path.as_deref().map(str::trim).filter(|p| !p.is_empty());
+    match server.auth_method.as_str() {
+        "password" => {
+            // Gli export senza segreti non hanno la password: il record resta importabile
+            if server.password.as_deref().map_or(true, |p| p.is_empty()) {
+                result.warning("password", "Metodo 'password' senza password salvata: va impostata prima di connettersi");
+            }
+            if key_path.is_some() {
+                result.warning("sshKeyPath", "Percorso chiave ignorato con il metodo 'password'");
+            }
+        }
+        "key" => {
+            match key_path {
+                Some(path) if !Path::new(path).exists() => {
+                    result.warning("sshKeyPath", format!("Il file chiave {} non esiste su questo computer", path));
+                }
+                None if server.ssh_key.trim().is_empty() => {
+                    result.warning("sshKeyPath", "Nessuna chiave indicata: verranno usati ssh-agent e le chiavi predefinite");
+                }
+                _ => {}
+            }
+            if server.password.as_deref().map_or(false, |p| !p.is_empty()) {
+                result.warning("password", "Password salvata ma non usata con il metodo 'key'");
+            }
+        }
+        other => result.error("authMethod", format!("Metodo di autenticazione non valido: '{}' (password o key)", other)),
+    }
+
+    match server.mac_address.as_deref().map(str::trim).filter(|m| !m.is_empty()) {
+        Some(mac) if !is_valid_mac(mac) => {
+            result.error("macAddress", format!("MAC '{}' non valido (formato AA:BB:CC:DD:EE:FF)", mac));
+        }
+        None if server.wol_enabled.unwrap_or(false) => {
+            result.warning("wolEnabled", "Wake-on-LAN abilitato senza MAC address");
+        }
+        _ => {}
+    }
+
+    if server.jump_host.as_deref().map_or(false, |j| !j.is_empty() && j == server.id) {
+        result.error("jumpHost", "Un server non può essere il jump host di sé stesso");
+    }
+
+    for forward in &server.port_forwards {
+        if let Err(e) = validate_forward(forward) {
+            result.error("portForwards", format!("{}: {}", forward.id, e));
+        }
+    }
+
+    result
+}
+
+// ✅ Parsing tollerante di un array JSON di server: ogni record viene deserializzato a parte,
+// così un record malformato non blocca l'import degli altri
+pub fn parse_server_records(content: &str) -> Result<(Vec<Server>, Vec<RecordValidation>), String> {
+    let values: Vec<serde_json::Value> =
+        serde_json::from_str(content).map_err(|e| format!("File JSON non valido: {}", e))?;
+
+    let mut servers = Vec::new();
+    let mut rejected = Vec::new();
+
+    for (index, value) in values.into_iter().enumerate() {
+        let name = value.get("name").and_then(|n| n.as_str()).map(|n| n.to_string());
+        let server_id = value.get("id").and_then(|n| n.as_str()).map(|n| n.to_string());
+
+        match serde_json::from_value::<Server>(value) {
+            Ok(server) => servers.push(server),
+            Err(e) => {
+                let mut result = RecordValidation { index, server_id, name, ..Default::default() };
+                result.error("record", format!("Record non valido: {}", e));
+                rejected.push(result);
+            }
+        }
+    }
+
+    Ok((servers, rejected))
+}
+
+// ✅ COMANDO: Valida un server prima del salvataggio (errori e warning per campo)
+#[command]
+pub fn validate_server_record(server: Server) -> RecordValidation {
+    validate_server(&server)
+}
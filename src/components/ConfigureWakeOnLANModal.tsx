@@ -12,7 +12,7 @@ import {
   import { useServer } from "@/context/useServer";
   import type { Server } from "@/context/ServerContext.types";
   import { toast } from "sonner";
-  import { saveServer, loadServers, deleteServerById } from "@/lib/serverStorage";
+  import { saveServer, loadServers } from "@/lib/serverStorage";
   import { Zap, AlertCircle } from "lucide-react";
   
   interface ConfigureWakeOnLANModalProps {
@@ -73,8 +73,7 @@ import {
       try {
         console.log("⚡ Configurando Wake-on-LAN per:", server.name);
         
-        // ✅ Aggiorna il server
-        await deleteServerById(server.id);
+        // ✅ Aggiorna il server (save_server sostituisce il record con lo stesso id)
         await saveServer(updatedServer);
         
         // ✅ Ricarica tutti i server
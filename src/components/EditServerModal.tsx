@@ -13,7 +13,7 @@ import {
   import { useServer } from "@/context/useServer";
   import type { Server } from "@/context/ServerContext.types";
   import { toast } from "sonner";
-  import { saveServer, loadServers, deleteServerById } from "@/lib/serverStorage";
+  import { saveServer, loadServers } from "@/lib/serverStorage";
   
   interface EditServerModalProps {
     server: Server;
@@ -93,8 +93,7 @@ import {
       try {
         console.log("📝 Aggiornando server:", updatedServer);
         
-        // ✅ Prima elimina il vecchio, poi salva il nuovo
-        await deleteServerById(server.id);
+        // ✅ save_server sostituisce il record con lo stesso id (se non è valido il vecchio resta intatto)
         await saveServer(updatedServer);
         
         // ✅ Ricarica tutti i server
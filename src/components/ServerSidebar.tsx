@@ -147,6 +147,7 @@ const ServerSidebar: React.FC = () => {
         sshPort: selectedServer.sshPort,
         password: selectedServer.password || null,
         customCommand: selectedServer.shutdownCommand || null,
+        serverId: selectedServer.id,
       });
 
       if (result.success) {
@@ -232,6 +233,7 @@ const ServerSidebar: React.FC = () => {
           ip: selectedServer.ip,
           sshPort: selectedServer.sshPort,
           password: selectedServer.password ?? null,
+          serverId: selectedServer.id,
         },
       });
 
@@ -1,5 +1,15 @@
 export type ServerStatus = 'online' | 'offline' | 'standby';
 
+export interface PortForward {
+  id: string;
+  kind: "local" | "remote" | "dynamic";
+  bindHost?: string;
+  bindPort: number;
+  targetHost?: string;
+  targetPort?: number;
+  autoRestart?: boolean;
+}
+
 export interface Server {
   id: string;
   name: string;
@@ -15,4 +25,8 @@ export interface Server {
   macAddress?: string;
   wolEnabled?: boolean;
   shutdownCommand?: string;
+  jumpHost?: string;
+  portForwards?: PortForward[];
+  pinnedServices?: string[];
+  tags?: string[];
 }
\ No newline at end of file
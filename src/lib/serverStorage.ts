@@ -14,7 +14,7 @@ export const loadServers = async (): Promise<Server[]> => {
   }
 };
 
-// 💾 Salva un singolo server
+// 💾 Salva un singolo server (nuovo o aggiornato: il backend sostituisce il record con lo stesso id)
 export const saveServer = async (server: Server): Promise<void> => {
   try {
     const rustServer = {
@@ -32,6 +32,10 @@ export const saveServer = async (server: Server): Promise<void> => {
       macAddress: server.macAddress || null,
       wolEnabled: server.wolEnabled || false,
       shutdownCommand: server.shutdownCommand || null,
+      jumpHost: server.jumpHost || null,
+      portForwards: server.portForwards || [],
+      pinnedServices: server.pinnedServices || [],
+      tags: server.tags || [],
     };
 
     await invoke("save_server", { server: rustServer });